@@ -0,0 +1,252 @@
+use log::{debug, info};
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::api::path::home_dir;
+
+// Oldest Anvil release we've verified state dumps and fork options against
+const MIN_ANVIL_VERSION: (u64, u64, u64) = (0, 2, 0);
+
+#[cfg(windows)]
+const ANVIL_BINARY: &str = "anvil.exe";
+#[cfg(not(windows))]
+const ANVIL_BINARY: &str = "anvil";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnvilBinary {
+    path: PathBuf,
+    version: String,
+    // Full `anvil --version` output, including commit and build timestamp
+    build: String,
+}
+
+impl AnvilBinary {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AnvilError {
+    NotFound {
+        searched: Vec<PathBuf>,
+    },
+    VersionCheckFailed {
+        path: PathBuf,
+        message: String,
+    },
+    Outdated {
+        path: PathBuf,
+        version: String,
+        required: String,
+    },
+}
+
+impl fmt::Display for AnvilError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnvilError::NotFound { .. } => write!(
+                f,
+                "Anvil not found. Please ensure Foundry is installed and Anvil is in your PATH."
+            ),
+            AnvilError::VersionCheckFailed { path, message } => write!(
+                f,
+                "Failed to check Anvil version at {}: {}",
+                path.display(),
+                message
+            ),
+            AnvilError::Outdated {
+                path,
+                version,
+                required,
+            } => write!(
+                f,
+                "Anvil {} at {} is too old, version {} or newer is required. Run `foundryup` to update.",
+                version,
+                path.display(),
+                required
+            ),
+        }
+    }
+}
+
+// Look for a usable Anvil binary, preferring user-configured paths.
+// The first candidate that passes the version check wins; if none does, the
+// error for the first binary found is reported.
+pub fn find_anvil(extra_paths: &[PathBuf]) -> Result<AnvilBinary, AnvilError> {
+    let candidates = candidate_paths(extra_paths);
+    let mut first_error = None;
+
+    for path in &candidates {
+        if !path.is_file() {
+            continue;
+        }
+        match check_version(path) {
+            Ok(binary) => {
+                info!("Using Anvil {} at {:?}", binary.version, binary.path);
+                return Ok(binary);
+            }
+            Err(e) => {
+                debug!("Skipping Anvil candidate {:?}: {}", path, e);
+                if first_error.is_none() {
+                    first_error = Some(e);
+                }
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or(AnvilError::NotFound {
+        searched: candidates,
+    }))
+}
+
+fn candidate_paths(extra_paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut paths = Vec::new();
+
+    // User-configured entries may point at the binary or its directory
+    for path in extra_paths {
+        if path.is_dir() {
+            dirs.push(path.clone());
+        } else {
+            paths.push(path.clone());
+        }
+    }
+
+    if let Some(foundry_dir) = std::env::var_os("FOUNDRY_DIR") {
+        dirs.push(PathBuf::from(foundry_dir).join("bin"));
+    }
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+
+    // GUI launches on macOS don't inherit the shell PATH, so check the usual
+    // foundryup, Homebrew, Cargo and Nix locations as well
+    if let Some(home) = home_dir() {
+        dirs.push(home.join(".foundry/bin"));
+        dirs.push(home.join(".cargo/bin"));
+        dirs.push(home.join(".nix-profile/bin"));
+    }
+    if let Some(user) = std::env::var_os("USER") {
+        dirs.push(
+            PathBuf::from("/etc/profiles/per-user")
+                .join(user)
+                .join("bin"),
+        );
+    }
+    dirs.extend(
+        [
+            "/opt/homebrew/bin",
+            "/usr/local/bin",
+            "/usr/bin",
+            "/nix/var/nix/profiles/default/bin",
+            "/run/current-system/sw/bin",
+        ]
+        .iter()
+        .map(PathBuf::from),
+    );
+
+    paths.extend(dirs.into_iter().map(|dir| dir.join(ANVIL_BINARY)));
+
+    let mut unique = Vec::new();
+    for path in paths {
+        if !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    unique
+}
+
+fn check_version(path: &Path) -> Result<AnvilBinary, AnvilError> {
+    let version_check_failed = |message: String| AnvilError::VersionCheckFailed {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let output = Command::new(path)
+        .arg("--version")
+        .output()
+        .map_err(|e| version_check_failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(version_check_failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    let build = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let version = parse_version(&build)
+        .ok_or_else(|| version_check_failed(format!("Unrecognized version output: {}", build)))?;
+
+    let version_string = format!("{}.{}.{}", version.0, version.1, version.2);
+    if version < MIN_ANVIL_VERSION {
+        let (major, minor, patch) = MIN_ANVIL_VERSION;
+        return Err(AnvilError::Outdated {
+            path: path.to_path_buf(),
+            version: version_string,
+            required: format!("{}.{}.{}", major, minor, patch),
+        });
+    }
+
+    Ok(AnvilBinary {
+        path: path.to_path_buf(),
+        version: version_string,
+        build,
+    })
+}
+
+// Handles both `anvil 0.2.0 (cba6e97 2024-09-30T00:20:33Z)` and the
+// multi-line `anvil Version: 1.0.0-stable` format of newer releases
+fn parse_version(output: &str) -> Option<(u64, u64, u64)> {
+    output.split_whitespace().find_map(|token| {
+        let token = token.trim_start_matches('v');
+        let core = token.split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+        let version = (parts.next()??, parts.next()??, parts.next()??);
+        parts.next().is_none().then_some(version)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_legacy_version_output() {
+        assert_eq!(
+            parse_version("anvil 0.2.0 (cba6e97 2024-09-30T00:20:33Z)"),
+            Some((0, 2, 0))
+        );
+    }
+
+    #[test]
+    fn parses_multi_line_version_output() {
+        let output = "anvil Version: 1.0.0-stable\n\
+                      Commit SHA: 1d9650e951204a0ddce9ff89c32f1997984cef4d\n\
+                      Build Timestamp: 2025-02-10T03:04:35.213563000Z (1739156675)\n\
+                      Build Profile: maxperf";
+        assert_eq!(parse_version(output), Some((1, 0, 0)));
+        assert_eq!(
+            parse_version("anvil Version: 1.2.3-nightly+abc123"),
+            Some((1, 2, 3))
+        );
+        assert_eq!(parse_version("anvil v1.1.0"), Some((1, 1, 0)));
+    }
+
+    #[test]
+    fn rejects_unrecognized_version_output() {
+        assert_eq!(parse_version(""), None);
+        assert_eq!(parse_version("anvil"), None);
+        assert_eq!(parse_version("anvil 1.0"), None);
+        assert_eq!(parse_version("anvil 1.0.0.0"), None);
+        assert_eq!(parse_version("Build Timestamp: 2025-02-10T03:04:35Z"), None);
+    }
+
+    #[test]
+    fn compares_against_minimum_version() {
+        assert!(parse_version("anvil 0.1.9 (abc 2024-01-01)").unwrap() < MIN_ANVIL_VERSION);
+        assert!(parse_version("anvil Version: 1.0.0-stable").unwrap() >= MIN_ANVIL_VERSION);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::endpoints::{resolve_endpoint, RpcEndpoint};
 use alloy_primitives::{eip191_hash_message, hex, Address, B256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, BlockNumberOrTag};
@@ -15,8 +16,26 @@ use foundry_common::{
     },
 };
 use serde_json;
+use std::collections::HashMap;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// Providers unused for this long are dropped, closing their connections
+const PROVIDER_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+struct CachedProvider {
+    provider: Arc<RetryProvider>,
+    // Settings the provider was built with, so edited endpoints get a new one
+    endpoint: RpcEndpoint,
+    last_used: Instant,
+}
+
+// Process-wide provider cache keyed by URL, shared by every command
+fn providers() -> &'static Mutex<HashMap<String, CachedProvider>> {
+    static PROVIDERS: OnceLock<Mutex<HashMap<String, CachedProvider>>> = OnceLock::new();
+    PROVIDERS.get_or_init(Default::default)
+}
 
 pub struct CastWrapper;
 
@@ -164,10 +183,57 @@ impl CastWrapper {
         SimpleCast::calldata_encode(sig, args)
     }
 
-    // Helper function to create a provider
+    // Helper function to create a provider. `rpc` is an endpoint name or an
+    // HTTP, WebSocket or IPC URL; providers are reused until they go idle.
     async fn get_provider(rpc: &str) -> Result<Arc<RetryProvider>> {
-        let provider = ProviderBuilder::new(rpc).build()?;
-        Ok(Arc::new(provider))
+        let endpoint = resolve_endpoint(rpc);
+        let mut providers = providers().lock().unwrap();
+        providers.retain(|_, cached| cached.last_used.elapsed() < PROVIDER_IDLE_TIMEOUT);
+
+        if let Some(cached) = providers.get_mut(&endpoint.url) {
+            if cached.endpoint.same_connection(&endpoint) {
+                cached.last_used = Instant::now();
+                return Ok(cached.provider.clone());
+            }
+        }
+
+        let provider = Arc::new(Self::build_provider(&endpoint)?);
+        providers.insert(
+            endpoint.url.clone(),
+            CachedProvider {
+                provider: provider.clone(),
+                endpoint,
+                last_used: Instant::now(),
+            },
+        );
+        Ok(provider)
+    }
+
+    // Foundry picks the transport from the URL: http(s), ws(s) or an IPC path
+    fn build_provider(endpoint: &RpcEndpoint) -> Result<RetryProvider> {
+        let mut builder = ProviderBuilder::new(&endpoint.url);
+        if let Some(timeout) = endpoint.timeout {
+            builder = builder.timeout(Duration::from_secs(timeout));
+        }
+        if let Some(retries) = endpoint.max_retries {
+            builder = builder.max_retry(retries);
+        }
+        if let Some(backoff) = endpoint.initial_backoff {
+            builder = builder.initial_backoff(backoff);
+        }
+        if let Some(cups) = endpoint.rate_limit {
+            builder = builder.compute_units_per_second(cups);
+        }
+        if !endpoint.headers.is_empty() {
+            builder = builder.headers(
+                endpoint
+                    .headers
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect(),
+            );
+        }
+        builder.build()
     }
 
     // Blockchain & RPC queries
@@ -386,6 +452,18 @@ impl CastWrapper {
             .to_string())
     }
 
+    // Raw JSON-RPC, for node-specific methods cast doesn't wrap
+    pub async fn rpc(
+        rpc: &str,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let provider = Self::get_provider(rpc).await?;
+        Ok(provider
+            .raw_request(method.to_string().into(), params)
+            .await?)
+    }
+
     // Misc
     pub fn keccak(data: &str) -> Result<String> {
         SimpleCast::keccak(data)
@@ -1,46 +1,728 @@
-use duckdb::polars::prelude as duckdb_polars;
-use duckdb::{Connection, Result};
-use log::info;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize)]
-pub struct QueryResult {
-    json: String,
-}
-
-pub fn execute_query(query: &str) -> Result<QueryResult> {
-    info!("Executing query: {}", query);
-
-    let conn = Connection::open_in_memory()?;
-    let mut stmt = conn.prepare(query)?;
-
-    let df: Vec<duckdb_polars::DataFrame> = stmt.query_polars([])?.collect::<Vec<_>>();
-
-    info!("DataFrame: {:?}", df);
-
-    // Convert DataFrame to JSON string
-    let json_result = df
-        .iter()
-        .map(|frame| {
-            let columns: Vec<(&str, Vec<String>)> = frame
-                .get_columns()
-                .iter()
-                .map(|series| {
-                    (
-                        series.name(),
-                        series.iter().map(|value| value.to_string()).collect(),
-                    )
-                })
-                .collect();
+use crate::query_result::QueryResult;
+use duckdb::{Connection, InterruptHandle, Result};
+use log::{error, info, warn};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Most rows a cursor keeps, unless the caller asks for a different cap
+const DEFAULT_ROW_CAP: u64 = 1_000_000;
+// Most rows a single page or streamed batch carries over IPC
+const MAX_PAGE_SIZE: u64 = 10_000;
+// Temp tables holding cursor results, hidden from the table list
+const CURSOR_TABLE_PREFIX: &str = "query_cursor_";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WorkspaceInfo {
+    // Database file, or None for the in-memory scratch database
+    path: Option<String>,
+}
+
+// One long-lived connection, so views, macros, temp tables and loaded
+// extensions survive between queries
+struct Workspace {
+    conn: Connection,
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TableInfo {
+    name: String,
+    // `BASE TABLE`, `VIEW` or `LOCAL TEMPORARY`, as DuckDB reports it
+    table_type: String,
+    columns: Vec<ColumnInfo>,
+    // None when counting fails, e.g. a view over files that were deleted
+    row_count: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ColumnInfo {
+    name: String,
+    data_type: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QueryCursor {
+    cursor_id: u64,
+    columns: Vec<ColumnInfo>,
+    // Rows held by the cursor, never more than the row cap
+    row_count: u64,
+    // Set when the query returned more rows than the cap
+    truncated: bool,
+    // Total rows of the uncapped query: exact unless truncated, in which case
+    // it's DuckDB's planner estimate (None if the planner has none)
+    total_rows: Option<u64>,
+    elapsed_ms: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QueryBatch {
+    cursor_id: u64,
+    offset: u64,
+    rows: QueryResult,
+    done: bool,
+}
+
+// A query's results, materialized into a temp table so pages can be read
+// without running the query again
+struct Cursor {
+    table: String,
+    row_count: u64,
+}
+
+#[derive(Default)]
+struct RunningQuery {
+    // Set once `tracked` has taken over the entry
+    claimed: bool,
+    // Set once the query holds the connection
+    handle: Option<Arc<InterruptHandle>>,
+    interrupted: bool,
+}
+
+// Chunk globs behind each view, by view name and then by DuckDB reader
+type ViewSources = BTreeMap<String, BTreeMap<String, BTreeSet<String>>>;
+
+pub struct ChainAnalyzer {
+    workspace: Mutex<Workspace>,
+    // Views over indexer output, kept so they can be recreated when
+    // switching workspaces or deleting runs
+    views: Mutex<ViewSources>,
+    cursors: Mutex<HashMap<u64, Cursor>>,
+    next_cursor: AtomicU64,
+    // Queries registered by `run_with_timeout` or started through `tracked`,
+    // by the id the caller gave them
+    running: Mutex<HashMap<String, RunningQuery>>,
+}
+
+impl ChainAnalyzer {
+    pub fn new() -> Result<Self> {
+        Ok(ChainAnalyzer {
+            workspace: Mutex::new(Workspace {
+                conn: Connection::open_in_memory()?,
+                path: None,
+            }),
+            views: Mutex::new(BTreeMap::new()),
+            cursors: Mutex::new(HashMap::new()),
+            next_cursor: AtomicU64::new(1),
+            running: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn workspace(&self) -> WorkspaceInfo {
+        self.workspace.lock().unwrap().info()
+    }
+
+    pub fn open_workspace(&self, path: PathBuf) -> Result<WorkspaceInfo, String> {
+        if !path.is_file() {
+            return Err(format!("Workspace database not found: {}", path.display()));
+        }
+        self.switch_workspace(Some(path))
+    }
+
+    pub fn create_workspace(&self, path: PathBuf) -> Result<WorkspaceInfo, String> {
+        if path.exists() {
+            return Err(format!("File already exists: {}", path.display()));
+        }
+        self.switch_workspace(Some(path))
+    }
+
+    // Go back to an empty in-memory database
+    pub fn close_workspace(&self) -> Result<WorkspaceInfo, String> {
+        self.switch_workspace(None)
+    }
+
+    fn switch_workspace(&self, path: Option<PathBuf>) -> Result<WorkspaceInfo, String> {
+        let conn = match &path {
+            Some(path) => Connection::open(path),
+            None => Connection::open_in_memory(),
+        }
+        .map_err(|e| e.to_string())?;
+
+        info!("Switching analyzer workspace to {:?}", path);
+        let mut workspace = self.workspace.lock().unwrap();
+        // Dropping the old connection closes it and flushes the file
+        *workspace = Workspace { conn, path };
+        // Cursor tables were temporary and went away with the old connection
+        self.cursors.lock().unwrap().clear();
+        for (name, sources) in self.views.lock().unwrap().iter() {
+            if let Err(e) = workspace.create_view(name, sources) {
+                error!("Failed to recreate view {}: {}", name, e);
+            }
+        }
+        Ok(workspace.info())
+    }
+
+    // Register cryo output files as views named after their network and
+    // dataset, e.g. `ethereum__logs`. Views read every matching chunk in the
+    // directories of all runs registered so far, so chunks added later show
+    // up without re-registering.
+    pub fn register_views(&self, files: &[String]) -> Vec<String> {
+        let workspace = self.workspace.lock().unwrap();
+        let mut views = self.views.lock().unwrap();
+        let mut registered = Vec::new();
+        for (name, added) in view_sources(files) {
+            let mut sources = views.get(&name).cloned().unwrap_or_default();
+            for (reader, globs) in added {
+                sources.entry(reader).or_default().extend(globs);
+            }
+            if views.get(&name) == Some(&sources) {
+                continue;
+            }
+            match workspace.create_view(&name, &sources) {
+                Ok(()) => {
+                    info!("Registered view {}", name);
+                    views.insert(name.clone(), sources);
+                    registered.push(name);
+                }
+                Err(e) => error!("Failed to register view {}: {}", name, e),
+            }
+        }
+        registered
+    }
+
+    // Rebuild every view from scratch over `files`, dropping views none of
+    // them belong to any more, e.g. after a run is deleted
+    pub fn rebuild_views(&self, files: &[String]) {
+        let workspace = self.workspace.lock().unwrap();
+        let mut views = self.views.lock().unwrap();
+        let sources = view_sources(files);
+        for name in views.keys().filter(|name| !sources.contains_key(*name)) {
+            info!("Dropping view {}", name);
+            if let Err(e) = workspace.drop_view(name) {
+                error!("Failed to drop view {}: {}", name, e);
+            }
+        }
+        views.clear();
+        for (name, sources) in sources {
+            match workspace.create_view(&name, &sources) {
+                Ok(()) => {
+                    views.insert(name, sources);
+                }
+                Err(e) => error!("Failed to register view {}: {}", name, e),
+            }
+        }
+    }
+
+    pub fn list_tables(&self) -> Result<Vec<TableInfo>> {
+        let workspace = self.workspace.lock().unwrap();
+        let conn = &workspace.conn;
+
+        let mut stmt = conn.prepare(
+            "SELECT table_name, table_type FROM information_schema.tables \
+             WHERE table_schema = 'main' AND NOT starts_with(table_name, ?) \
+             ORDER BY table_name",
+        )?;
+        let tables = stmt
+            .query_map([CURSOR_TABLE_PREFIX], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut columns_stmt = conn.prepare(
+            "SELECT column_name, data_type FROM information_schema.columns \
+             WHERE table_schema = 'main' AND table_name = ? ORDER BY ordinal_position",
+        )?;
+        let mut infos = Vec::new();
+        for (name, table_type) in tables {
+            let columns = columns_stmt
+                .query_map([&name], |row| {
+                    Ok(ColumnInfo {
+                        name: row.get(0)?,
+                        data_type: row.get(1)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>>>()?;
+            let row_count = conn
+                .query_row(
+                    &format!("SELECT count(*) FROM {}", sql_identifier(&name)),
+                    [],
+                    |row| row.get::<_, u64>(0),
+                )
+                .ok();
+            infos.push(TableInfo {
+                name,
+                table_type,
+                columns,
+                row_count,
+            });
+        }
+        Ok(infos)
+    }
+
+    pub fn execute_query(&self, query_id: &str, query: &str) -> Result<QueryResult, String> {
+        info!("Executing query {}: {}", query_id, query);
+
+        let started = Instant::now();
+        let result = self.tracked(query_id, |workspace| {
+            let mut stmt = workspace.conn.prepare(query)?;
+            QueryResult::from_rows(stmt.query([])?, started)
+        })?;
 
-            serde_json::to_string(&columns).unwrap()
+        info!("Query {} returned {} rows", query_id, result.row_count());
+        Ok(result)
+    }
+
+    // Run a query into a cursor, keeping at most `row_cap` rows. Only queries
+    // that can be used as a subquery (SELECT, FROM, VALUES...) are supported.
+    pub fn open_cursor(
+        &self,
+        query_id: &str,
+        query: &str,
+        row_cap: Option<u64>,
+    ) -> Result<QueryCursor, String> {
+        info!("Opening cursor for query {}: {}", query_id, query);
+
+        let started = Instant::now();
+        let row_cap = row_cap.unwrap_or(DEFAULT_ROW_CAP);
+        let query = query.trim().trim_end_matches(';');
+        let cursor_id = self.next_cursor.fetch_add(1, Ordering::Relaxed);
+        let table = format!("{}{}", CURSOR_TABLE_PREFIX, cursor_id);
+
+        self.tracked(query_id, |workspace| {
+            // One row past the cap tells whether the result was cut short.
+            // The newline ends any trailing `--` comment in the query.
+            workspace.conn.execute_batch(&format!(
+                "CREATE TEMP TABLE {} AS SELECT * FROM ({}\n) LIMIT {}",
+                table,
+                query,
+                row_cap.saturating_add(1)
+            ))?;
+            let cursor = workspace.describe_cursor(&table, query, row_cap).map(
+                |(columns, row_count, truncated, total_rows)| QueryCursor {
+                    cursor_id,
+                    columns,
+                    row_count,
+                    truncated,
+                    total_rows,
+                    elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+                },
+            );
+            match &cursor {
+                Ok(cursor) => {
+                    self.cursors.lock().unwrap().insert(
+                        cursor_id,
+                        Cursor {
+                            table: table.clone(),
+                            row_count: cursor.row_count,
+                        },
+                    );
+                    info!("Cursor {} holds {} rows", cursor_id, cursor.row_count);
+                }
+                // Don't leave a half-built cursor behind, e.g. when interrupted
+                Err(_) => {
+                    let _ = workspace
+                        .conn
+                        .execute_batch(&format!("DROP TABLE IF EXISTS {}", table));
+                }
+            }
+            cursor
+        })
+    }
+
+    // Stop a running query. Queries still waiting for the workspace are
+    // marked so they fail as soon as they get it.
+    pub fn interrupt_query(&self, query_id: &str) -> Result<(), String> {
+        let mut running = self.running.lock().unwrap();
+        let query = running
+            .get_mut(query_id)
+            .ok_or_else(|| format!("No running query with id {}", query_id))?;
+        info!("Interrupting query {}", query_id);
+        query.interrupted = true;
+        if let Some(handle) = &query.handle {
+            handle.interrupt();
+        }
+        Ok(())
+    }
+
+    // Run `run` on a blocking thread. Everything that touches the workspace
+    // goes through here, since it may have to wait for a long query.
+    pub async fn run_blocking<T: Send + 'static>(
+        self: &Arc<Self>,
+        run: impl FnOnce(&ChainAnalyzer) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let analyzer = Arc::clone(self);
+        tokio::task::spawn_blocking(move || run(&analyzer))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    // Like `run_blocking`, but interrupts the query if it takes longer than
+    // `timeout`, including time spent waiting for the workspace
+    pub async fn run_with_timeout<T: Send + 'static>(
+        self: &Arc<Self>,
+        query_id: String,
+        timeout: Option<Duration>,
+        run: impl FnOnce(&ChainAnalyzer) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        // Registered before the blocking thread starts, so the query can be
+        // interrupted (or timed out) while it's still queued
+        {
+            let mut running = self.running.lock().unwrap();
+            if running.contains_key(&query_id) {
+                return Err(format!("Query {} is already running", query_id));
+            }
+            running.insert(query_id.clone(), RunningQuery::default());
+        }
+
+        let task = self.run_blocking(run);
+        let Some(timeout) = timeout else {
+            return task.await;
+        };
+        match tokio::time::timeout(timeout, task).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Query {} timed out after {:?}", query_id, timeout);
+                // The blocking thread carries on until DuckDB unwinds, or
+                // bails as soon as it gets the workspace if it was waiting
+                let _ = self.interrupt_query(&query_id);
+                Err(format!(
+                    "Query timed out after {:.1}s",
+                    timeout.as_secs_f64()
+                ))
+            }
+        }
+    }
+
+    // Run `run` against the workspace under `query_id`, so it can be
+    // interrupted while it holds the connection
+    fn tracked<T>(
+        &self,
+        query_id: &str,
+        run: impl FnOnce(&Workspace) -> Result<T>,
+    ) -> Result<T, String> {
+        {
+            let mut running = self.running.lock().unwrap();
+            let query = running.entry(query_id.to_string()).or_default();
+            if query.claimed {
+                return Err(format!("Query {} is already running", query_id));
+            }
+            query.claimed = true;
+        }
+
+        let workspace = self.workspace.lock().unwrap();
+        {
+            let mut running = self.running.lock().unwrap();
+            let query = running.get_mut(query_id).unwrap();
+            if query.interrupted {
+                running.remove(query_id);
+                return Err(format!("Query {} was interrupted", query_id));
+            }
+            query.handle = Some(workspace.conn.interrupt_handle());
+        }
+        let result = run(&workspace);
+        // Removed before the workspace is unlocked, so an interrupt can't
+        // reach whichever query runs next
+        let query = self.running.lock().unwrap().remove(query_id);
+        drop(workspace);
+
+        result.map_err(|e| match query {
+            Some(query) if query.interrupted => format!("Query {} was interrupted", query_id),
+            _ => e.to_string(),
         })
-        .collect::<Vec<String>>()
-        .join(",");
+    }
+
+    pub fn fetch_page(
+        &self,
+        cursor_id: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<QueryResult, String> {
+        let table = self.cursor(cursor_id)?.0;
+        let started = Instant::now();
+        let workspace = self.workspace.lock().unwrap();
+        let mut stmt = workspace
+            .conn
+            .prepare(&format!(
+                "SELECT * FROM {} ORDER BY rowid LIMIT {} OFFSET {}",
+                table,
+                limit.min(MAX_PAGE_SIZE),
+                offset
+            ))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query([]).map_err(|e| e.to_string())?;
+        QueryResult::from_rows(rows, started).map_err(|e| e.to_string())
+    }
+
+    // Read the whole cursor in batches, handing each one to `emit`. The
+    // workspace is unlocked between batches so other queries can run, and
+    // closing the cursor stops the stream.
+    pub fn stream_cursor(
+        &self,
+        cursor_id: u64,
+        batch_size: u64,
+        mut emit: impl FnMut(QueryBatch),
+    ) -> Result<(), String> {
+        let row_count = self.cursor(cursor_id)?.1;
+        let batch_size = batch_size.clamp(1, MAX_PAGE_SIZE);
+        let mut offset = 0;
+        loop {
+            let rows = self.fetch_page(cursor_id, offset, batch_size)?;
+            let next = offset + rows.row_count() as u64;
+            let done = next >= row_count || rows.row_count() == 0;
+            emit(QueryBatch {
+                cursor_id,
+                offset,
+                rows,
+                done,
+            });
+            if done {
+                return Ok(());
+            }
+            offset = next;
+        }
+    }
+
+    pub fn close_cursor(&self, cursor_id: u64) -> Result<(), String> {
+        let cursor = self
+            .cursors
+            .lock()
+            .unwrap()
+            .remove(&cursor_id)
+            .ok_or_else(|| format!("Unknown query cursor: {}", cursor_id))?;
+        let workspace = self.workspace.lock().unwrap();
+        workspace
+            .conn
+            .execute_batch(&format!("DROP TABLE IF EXISTS {}", cursor.table))
+            .map_err(|e| e.to_string())
+    }
+
+    fn cursor(&self, cursor_id: u64) -> Result<(String, u64), String> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .get(&cursor_id)
+            .map(|cursor| (cursor.table.clone(), cursor.row_count))
+            .ok_or_else(|| format!("Unknown query cursor: {}", cursor_id))
+    }
+}
+
+impl Workspace {
+    // Runs can pick different columns, so chunks are matched up by column
+    // name rather than position
+    fn create_view(&self, name: &str, sources: &BTreeMap<String, BTreeSet<String>>) -> Result<()> {
+        let selects: Vec<String> = sources
+            .iter()
+            .map(|(reader, globs)| {
+                let globs: Vec<String> = globs.iter().map(|glob| sql_string(glob)).collect();
+                format!(
+                    "SELECT * FROM {}([{}], union_by_name = true)",
+                    reader,
+                    globs.join(", ")
+                )
+            })
+            .collect();
+        self.conn.execute_batch(&format!(
+            "CREATE OR REPLACE VIEW {} AS {}",
+            sql_identifier(name),
+            selects.join(" UNION ALL BY NAME ")
+        ))
+    }
+
+    fn drop_view(&self, name: &str) -> Result<()> {
+        self.conn
+            .execute_batch(&format!("DROP VIEW IF EXISTS {}", sql_identifier(name)))
+    }
+
+    // The planner's cardinality estimate for the query's root operator
+    fn estimate_rows(&self, query: &str) -> Option<u64> {
+        let plan: String = self
+            .conn
+            .query_row(&format!("EXPLAIN (FORMAT JSON) {}", query), [], |row| {
+                row.get(1)
+            })
+            .ok()?;
+        let plan: JsonValue = serde_json::from_str(&plan).ok()?;
+        let root = plan.get(0)?;
+        match root.get("extra_info")? {
+            // DuckDB 1.1 and later
+            JsonValue::Object(info) => info.get("Estimated Cardinality")?.as_str()?.parse().ok(),
+            // DuckDB 1.0 packs everything into one string, ending in `EC: <rows>`
+            JsonValue::String(info) => {
+                let estimate = &info[info.rfind("EC:")? + 3..];
+                estimate
+                    .trim_start()
+                    .split(|c: char| !c.is_ascii_digit())
+                    .next()?
+                    .parse()
+                    .ok()
+            }
+            _ => None,
+        }
+    }
+
+    // Columns, row count, truncation and total rows of a freshly created
+    // cursor table, trimming it back to `row_cap` rows
+    fn describe_cursor(
+        &self,
+        table: &str,
+        query: &str,
+        row_cap: u64,
+    ) -> Result<(Vec<ColumnInfo>, u64, bool, Option<u64>)> {
+        let mut row_count =
+            self.conn
+                .query_row(&format!("SELECT count(*) FROM {}", table), [], |row| {
+                    row.get::<_, u64>(0)
+                })?;
+        let truncated = row_count > row_cap;
+        let total_rows = if truncated {
+            self.conn
+                .execute_batch(&format!("DELETE FROM {} WHERE rowid >= {}", table, row_cap))?;
+            row_count = row_cap;
+            self.estimate_rows(query)
+                .filter(|&estimate| estimate > row_cap)
+        } else {
+            Some(row_count)
+        };
+
+        let columns = self
+            .conn
+            .prepare(&format!("DESCRIBE {}", table))?
+            .query_map([], |row| {
+                Ok(ColumnInfo {
+                    name: row.get(0)?,
+                    data_type: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok((columns, row_count, truncated, total_rows))
+    }
+
+    fn info(&self) -> WorkspaceInfo {
+        WorkspaceInfo {
+            path: self
+                .path
+                .as_ref()
+                .map(|path| path.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+fn view_sources(files: &[String]) -> ViewSources {
+    let mut sources = ViewSources::new();
+    for file in files {
+        if let Some((name, reader, glob)) = view_source(Path::new(file)) {
+            sources
+                .entry(name)
+                .or_default()
+                .entry(reader)
+                .or_default()
+                .insert(glob);
+        }
+    }
+    sources
+}
+
+// Cryo names files `<network>__<dataset>__<chunk>[__<label>].<ext>`; returns
+// the view name, DuckDB reader and a glob over the dataset's chunks
+fn view_source(file: &Path) -> Option<(String, String, String)> {
+    let extension = file.extension()?.to_str()?;
+    let format = match extension {
+        "parquet" => "read_parquet",
+        "csv" => "read_csv_auto",
+        "json" => "read_json_auto",
+        _ => return None,
+    };
+    let stem = file.file_stem()?.to_str()?;
+    let mut parts = stem.split("__");
+    let (network, dataset) = (parts.next()?, parts.next()?);
+    parts.next()?;
+
+    let name = format!("{}__{}", network, dataset);
+    let glob = file
+        .parent()?
+        .join(format!("{}__*.{}", name, extension))
+        .to_string_lossy()
+        .into_owned();
+    Some((name, format.to_string(), glob))
+}
+
+fn sql_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(name: &str, reader: &str, glob: &str) -> Option<(String, String, String)> {
+        Some((name.to_string(), reader.to_string(), glob.to_string()))
+    }
+
+    #[test]
+    fn reads_cryo_file_names() {
+        assert_eq!(
+            view_source(Path::new(
+                "/data/ethereum__blocks__00017000000_to_00017000999.parquet"
+            )),
+            source(
+                "ethereum__blocks",
+                "read_parquet",
+                "/data/ethereum__blocks__*.parquet"
+            )
+        );
+        assert_eq!(
+            view_source(Path::new(
+                "/data/logs/base__logs__00000000_to_00000999__mylabel.csv"
+            )),
+            source(
+                "base__logs",
+                "read_csv_auto",
+                "/data/logs/base__logs__*.csv"
+            )
+        );
+        assert_eq!(
+            view_source(Path::new("/data/ethereum__transactions__0.json")),
+            source(
+                "ethereum__transactions",
+                "read_json_auto",
+                "/data/ethereum__transactions__*.json"
+            )
+        );
+    }
 
-    let result = format!("[{}]", json_result);
+    #[test]
+    fn skips_other_files() {
+        for file in [
+            "/data/blocks.parquet",
+            "/data/ethereum__blocks.parquet",
+            "/data/ethereum__blocks__0.parquet_tmp",
+            "/data/ethereum__blocks__0.txt",
+            "/data/ethereum__blocks__0",
+        ] {
+            assert_eq!(view_source(Path::new(file)), None, "{}", file);
+        }
+    }
 
-    info!("JSON result: {}", result);
+    #[test]
+    fn groups_chunks_by_dataset_and_format() {
+        let sources = view_sources(&[
+            "/data/ethereum__blocks__0_to_999.parquet".to_string(),
+            "/data/ethereum__blocks__1000_to_1999.parquet".to_string(),
+            "/other/ethereum__blocks__0_to_999.csv".to_string(),
+            "/data/ethereum__logs__0_to_999.parquet".to_string(),
+            "/data/notes.txt".to_string(),
+        ]);
+        assert_eq!(
+            sources.keys().collect::<Vec<_>>(),
+            ["ethereum__blocks", "ethereum__logs"]
+        );
 
-    Ok(QueryResult { json: result })
+        let blocks = &sources["ethereum__blocks"];
+        assert_eq!(
+            blocks["read_parquet"].iter().collect::<Vec<_>>(),
+            ["/data/ethereum__blocks__*.parquet"]
+        );
+        assert_eq!(
+            blocks["read_csv_auto"].iter().collect::<Vec<_>>(),
+            ["/other/ethereum__blocks__*.csv"]
+        );
+    }
 }
@@ -0,0 +1,144 @@
+use cryo_freeze::{Datatype, Dim};
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetSchema {
+    name: String,
+    aliases: Vec<String>,
+    columns: Vec<DatasetColumn>,
+    default_columns: Vec<String>,
+    // Dimensions that must be given for the dataset, e.g. `address` or `slot`
+    required_parameters: Vec<String>,
+    optional_parameters: Vec<String>,
+    default_sort: Vec<String>,
+    default_blocks: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetColumn {
+    name: String,
+    column_type: String,
+    default: bool,
+}
+
+impl DatasetSchema {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|alias| alias == name)
+    }
+
+    pub fn has_column(&self, column: &str) -> bool {
+        self.columns.iter().any(|c| c.name == column)
+    }
+
+    pub fn required_parameters(&self) -> &[String] {
+        &self.required_parameters
+    }
+}
+
+#[cfg(test)]
+impl DatasetSchema {
+    pub fn for_test(name: &str, columns: &[&str], required_parameters: &[&str]) -> Self {
+        DatasetSchema {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            columns: columns
+                .iter()
+                .map(|column| DatasetColumn {
+                    name: column.to_string(),
+                    column_type: "binary".to_string(),
+                    default: true,
+                })
+                .collect(),
+            default_columns: columns.iter().map(|c| c.to_string()).collect(),
+            required_parameters: required_parameters.iter().map(|p| p.to_string()).collect(),
+            optional_parameters: Vec::new(),
+            default_sort: Vec::new(),
+            default_blocks: None,
+        }
+    }
+}
+
+// Build the catalog from cryo's datatype registry so it stays in sync with
+// the cryo version we ship
+pub fn dataset_catalog() -> Vec<DatasetSchema> {
+    let mut catalog: Vec<DatasetSchema> = Datatype::all().iter().map(dataset_schema).collect();
+    catalog.sort_by(|a, b| a.name.cmp(&b.name));
+    catalog
+}
+
+fn dataset_schema(datatype: &Datatype) -> DatasetSchema {
+    let column_types = datatype.column_types();
+    // Cryo keeps column types in a HashMap, so other columns are sorted by
+    // name to keep the order stable
+    let mut other_columns: Vec<String> = column_types.keys().map(|c| c.to_string()).collect();
+    other_columns.sort();
+    // Cryo collects every column when a dataset doesn't list defaults
+    let default_columns: Vec<String> = match datatype.default_columns() {
+        Some(columns) => columns.iter().map(|c| c.to_string()).collect(),
+        None => other_columns.clone(),
+    };
+    other_columns.retain(|c| !default_columns.contains(c));
+
+    // Defaults first, in the order cryo declares them
+    let columns = default_columns
+        .iter()
+        .chain(other_columns.iter())
+        .filter_map(|name| {
+            let column_type = column_types.get(name.as_str())?;
+            Some(DatasetColumn {
+                name: name.clone(),
+                column_type: column_type.as_str().to_string(),
+                default: default_columns.contains(name),
+            })
+        })
+        .collect();
+
+    DatasetSchema {
+        name: datatype.name(),
+        aliases: datatype
+            .aliases()
+            .iter()
+            .map(|alias| alias.to_string())
+            .collect(),
+        columns,
+        default_columns,
+        required_parameters: datatype
+            .required_parameters()
+            .iter()
+            .map(parameter_name)
+            .collect(),
+        optional_parameters: datatype
+            .optional_parameters()
+            .iter()
+            .map(parameter_name)
+            .collect(),
+        default_sort: datatype.default_sort().unwrap_or_default(),
+        default_blocks: datatype.default_blocks(),
+    }
+}
+
+// Name of the `IndexerOptions` field that supplies a dimension
+fn parameter_name(dim: &Dim) -> String {
+    match dim {
+        Dim::BlockNumber => "blocks",
+        Dim::TransactionHash => "txs",
+        Dim::CallData => "callData",
+        Dim::Address => "address",
+        Dim::Contract => "contract",
+        Dim::FromAddress => "fromAddress",
+        Dim::ToAddress => "toAddress",
+        Dim::Slot => "slot",
+        Dim::Topic0 => "topic0",
+        Dim::Topic1 => "topic1",
+        Dim::Topic2 => "topic2",
+        Dim::Topic3 => "topic3",
+        _ => dim.plural_name(),
+    }
+    .to_string()
+}
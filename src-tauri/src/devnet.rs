@@ -0,0 +1,1128 @@
+use crate::anvil::{find_anvil, AnvilBinary, AnvilError};
+use crate::cast_wrapper::CastWrapper;
+use crate::endpoints::resolve_endpoint;
+use crate::persist::{set_aside, write_atomic};
+use alloy::{
+    hex,
+    node_bindings::anvil::{Anvil, AnvilInstance},
+};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types::BlockId;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+// Instance id used when a command doesn't name one
+pub const DEFAULT_DEVNET_ID: &str = "default";
+
+// Maximum number of Anvil log lines kept in memory per instance
+const MAX_DEVNET_LOGS: usize = 1000;
+
+// Number of trailing log lines sent with a `devnet-exited` event
+const EXIT_LOG_LINES: usize = 50;
+
+// Give up auto-restarting a devnet that keeps crashing
+const MAX_DEVNET_RESTARTS: u32 = 5;
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DevnetInfo {
+    id: String,
+    port: u16,
+    rpc_url: String,
+    chain_id: u64,
+    client_version: String,
+    addresses: Vec<String>,
+    private_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DevnetConfig {
+    pub port: Option<u16>,
+    pub chain_id: Option<u64>,
+    pub accounts: Option<u64>,
+    pub mnemonic: Option<String>,
+    // Initial balance of each dev account, in ether
+    pub balance: Option<u64>,
+    // Seconds between blocks; interval mining is disabled when unset
+    pub block_time: Option<u64>,
+    pub no_mining: bool,
+    pub gas_limit: Option<u128>,
+    pub base_fee: Option<u128>,
+    pub hardfork: Option<String>,
+    pub code_size_limit: Option<usize>,
+    pub steps_tracing: bool,
+    // Name of a saved state dump to load once the devnet is up
+    pub load_state: Option<String>,
+    // Relaunch the devnet with the same config if the Anvil process exits
+    pub auto_restart: bool,
+    // Seconds between automatic state dumps, restored on auto-restart
+    pub state_interval: Option<u64>,
+    // Name of the saved state written when the app exits
+    pub dump_state_on_exit: Option<String>,
+}
+
+impl DevnetConfig {
+    fn apply(&self, mut anvil: Anvil) -> Anvil {
+        if let Some(port) = self.port {
+            anvil = anvil.port(port);
+        }
+        if let Some(chain_id) = self.chain_id {
+            anvil = anvil.chain_id(chain_id);
+        }
+        if let Some(mnemonic) = &self.mnemonic {
+            anvil = anvil.mnemonic(mnemonic);
+        }
+        if let Some(block_time) = self.block_time {
+            anvil = anvil.block_time(block_time);
+        }
+        if let Some(accounts) = self.accounts {
+            anvil = anvil.args(["--accounts".to_string(), accounts.to_string()]);
+        }
+        if let Some(balance) = self.balance {
+            anvil = anvil.args(["--balance".to_string(), balance.to_string()]);
+        }
+        if self.no_mining {
+            anvil = anvil.arg("--no-mining");
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            anvil = anvil.args(["--gas-limit".to_string(), gas_limit.to_string()]);
+        }
+        if let Some(base_fee) = self.base_fee {
+            anvil = anvil.args(["--base-fee".to_string(), base_fee.to_string()]);
+        }
+        if let Some(hardfork) = &self.hardfork {
+            anvil = anvil.args(["--hardfork", hardfork.as_str()]);
+        }
+        if let Some(code_size_limit) = self.code_size_limit {
+            anvil = anvil.args(["--code-size-limit".to_string(), code_size_limit.to_string()]);
+        }
+        if self.steps_tracing {
+            anvil = anvil.arg("--steps-tracing");
+        }
+        anvil
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ForkOptions {
+    pub block_number: Option<u64>,
+    // Resolved to a block number against the fork URL before spawning
+    pub block_hash: Option<String>,
+    pub retries: Option<u32>,
+    pub compute_units_per_second: Option<u64>,
+    // Chain id of the forked network, skips fetching it from the RPC
+    pub chain_id: Option<u64>,
+}
+
+impl ForkOptions {
+    async fn apply(&self, url: &str, mut anvil: Anvil) -> Result<Anvil, String> {
+        if self.block_number.is_some() && self.block_hash.is_some() {
+            return Err("Fork block number and block hash are mutually exclusive".to_string());
+        }
+
+        // `url` may also name a registered RPC endpoint
+        let endpoint = resolve_endpoint(url);
+        anvil = anvil.fork(endpoint.url);
+        for (name, value) in &endpoint.headers {
+            anvil = anvil.args(["--fork-header".to_string(), format!("{}: {}", name, value)]);
+        }
+        if let Some(block_number) = self.block_number {
+            anvil = anvil.fork_block_number(block_number);
+        }
+        if let Some(block_hash) = &self.block_hash {
+            anvil = anvil.fork_block_number(resolve_block_hash(url, block_hash).await?);
+        }
+        if let Some(retries) = self.retries {
+            anvil = anvil.args(["--retries".to_string(), retries.to_string()]);
+        }
+        if let Some(cups) = self.compute_units_per_second.or(endpoint.rate_limit) {
+            anvil = anvil.args(["--compute-units-per-second".to_string(), cups.to_string()]);
+        }
+        if let Some(chain_id) = self.chain_id {
+            anvil = anvil.args(["--fork-chain-id".to_string(), chain_id.to_string()]);
+        }
+        Ok(anvil)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedDevnetState {
+    name: String,
+    path: String,
+    size: u64,
+    // Seconds since the Unix epoch
+    modified: u64,
+}
+
+// A named, shareable launch setup. Profiles with a fork are launched through
+// `fork_network`, the rest through `start_devnet`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevnetProfile {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub fork: Option<ForkProfile>,
+    #[serde(default)]
+    pub config: DevnetConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkProfile {
+    pub url: String,
+    #[serde(default)]
+    pub options: ForkOptions,
+}
+
+// Anvil processes spawned by RaaR, recorded so they can be cleaned up if the
+// app is killed before it gets to stop them
+#[derive(Debug, Serialize, Deserialize)]
+struct DevnetProcess {
+    pid: u32,
+    port: u16,
+}
+
+#[derive(Serialize, Clone)]
+struct DevnetLog {
+    id: String,
+    line: String,
+}
+
+#[derive(Serialize, Clone)]
+struct DevnetExit {
+    id: String,
+    exit_code: Option<i32>,
+    logs: Vec<String>,
+    restarting: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct DevnetRestartError {
+    id: String,
+    error: String,
+}
+
+// An exited devnet waiting to be relaunched
+struct DevnetRestart {
+    id: String,
+    launch: DevnetLaunch,
+    config: DevnetConfig,
+    restarts: u32,
+    window: tauri::Window,
+}
+
+#[derive(Debug, Clone)]
+enum DevnetLaunch {
+    Local,
+    Fork { url: String, options: ForkOptions },
+}
+
+pub struct Devnet {
+    instance: AnvilInstance,
+    // Forks report the remote chain id, which AnvilInstance doesn't know about
+    chain_id: u64,
+    logs: Arc<Mutex<VecDeque<String>>>,
+    // Everything needed to relaunch the devnet after a crash
+    launch: DevnetLaunch,
+    config: DevnetConfig,
+    restarts: u32,
+    window: tauri::Window,
+}
+
+impl Devnet {
+    fn new(
+        id: &str,
+        mut instance: AnvilInstance,
+        chain_id: u64,
+        launch: DevnetLaunch,
+        config: DevnetConfig,
+        window: tauri::Window,
+    ) -> Self {
+        let logs = Arc::new(Mutex::new(VecDeque::with_capacity(MAX_DEVNET_LOGS)));
+        capture_devnet_logs(id.to_string(), &mut instance, logs.clone(), window.clone());
+        Devnet {
+            instance,
+            chain_id,
+            logs,
+            launch,
+            config,
+            restarts: 0,
+            window,
+        }
+    }
+
+    pub fn info(&self, id: &str) -> DevnetInfo {
+        DevnetInfo {
+            id: id.to_string(),
+            port: self.instance.port(),
+            rpc_url: self.instance.endpoint(),
+            chain_id: self.chain_id,
+            client_version: "Anvil".to_string(),
+            addresses: self
+                .instance
+                .addresses()
+                .iter()
+                .map(|addr| format!("{:?}", addr))
+                .collect(),
+            private_keys: self
+                .instance
+                .keys()
+                .iter()
+                .map(|key| format!("0x{}", hex::encode(key.to_bytes())))
+                .collect(),
+        }
+    }
+
+    pub fn wallets(&self) -> Vec<serde_json::Value> {
+        let accounts = self.instance.addresses();
+        let private_keys = self.instance.keys();
+
+        accounts
+            .iter()
+            .zip(private_keys)
+            .map(|(address, private_key)| {
+                serde_json::json!({
+                    "address": format!("{:?}", address),
+                    "privateKey": format!("0x{}", hex::encode(private_key.to_bytes())),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn logs(&self) -> Vec<String> {
+        self.logs.lock().await.iter().cloned().collect()
+    }
+
+    pub fn client(&self) -> DevnetClient {
+        DevnetClient {
+            endpoint: self.instance.endpoint(),
+        }
+    }
+}
+
+// Sends RPC calls to a devnet without borrowing it, so callers can release
+// the devnet state lock before awaiting the response
+#[derive(Debug, Clone)]
+pub struct DevnetClient {
+    endpoint: String,
+}
+
+impl DevnetClient {
+    pub async fn dump_state(
+        &self,
+        app: &tauri::AppHandle,
+        name: &str,
+    ) -> Result<SavedDevnetState, String> {
+        let path = saved_state_path(app, name)?;
+        let dump = self.rpc("anvil_dumpState", serde_json::json!([])).await?;
+        let dump = dump.as_str().ok_or("Unexpected anvil_dumpState response")?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, dump).map_err(|e| e.to_string())?;
+        saved_state(name, &path)
+    }
+
+    pub async fn load_state(&self, app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+        let path = saved_state_path(app, name)?;
+        let dump = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read saved state {}: {}", name, e))?;
+        self.rpc("anvil_loadState", serde_json::json!([dump.trim()]))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn snapshot(&self) -> Result<String, String> {
+        let snapshot = self.rpc("evm_snapshot", serde_json::json!([])).await?;
+        snapshot
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Unexpected evm_snapshot response".to_string())
+    }
+
+    pub async fn revert(&self, snapshot_id: &str) -> Result<bool, String> {
+        let reverted = self
+            .rpc("evm_revert", serde_json::json!([snapshot_id]))
+            .await?;
+        Ok(reverted.as_bool().unwrap_or(false))
+    }
+
+    // Cheat codes
+    pub async fn impersonate_account(&self, address: &str, enabled: bool) -> Result<(), String> {
+        let method = if enabled {
+            "anvil_impersonateAccount"
+        } else {
+            "anvil_stopImpersonatingAccount"
+        };
+        self.rpc(method, serde_json::json!([parse_address(address)?]))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_balance(&self, address: &str, wei: &str) -> Result<(), String> {
+        let params = serde_json::json!([parse_address(address)?, parse_u256(wei)?]);
+        self.rpc("anvil_setBalance", params).await?;
+        Ok(())
+    }
+
+    pub async fn set_code(&self, address: &str, code: &str) -> Result<(), String> {
+        let code = Bytes::from_str(code).map_err(|e| format!("Invalid bytecode: {}", e))?;
+        let params = serde_json::json!([parse_address(address)?, code]);
+        self.rpc("anvil_setCode", params).await?;
+        Ok(())
+    }
+
+    pub async fn set_storage_at(
+        &self,
+        address: &str,
+        slot: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        let params = serde_json::json!([
+            parse_address(address)?,
+            parse_u256(slot)?,
+            B256::from(parse_u256(value)?),
+        ]);
+        self.rpc("anvil_setStorageAt", params).await?;
+        Ok(())
+    }
+
+    pub async fn set_nonce(&self, address: &str, nonce: u64) -> Result<(), String> {
+        let params = serde_json::json!([parse_address(address)?, U256::from(nonce)]);
+        self.rpc("anvil_setNonce", params).await?;
+        Ok(())
+    }
+
+    // Returns the total time offset applied to the chain, in seconds
+    pub async fn increase_time(&self, seconds: u64) -> Result<i64, String> {
+        let offset = self
+            .rpc("evm_increaseTime", serde_json::json!([U256::from(seconds)]))
+            .await?;
+        offset
+            .as_i64()
+            .ok_or_else(|| "Unexpected evm_increaseTime response".to_string())
+    }
+
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), String> {
+        self.rpc("evm_setNextBlockTimestamp", serde_json::json!([timestamp]))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_automine(&self, enabled: bool) -> Result<(), String> {
+        self.rpc("anvil_setAutomine", serde_json::json!([enabled]))
+            .await?;
+        Ok(())
+    }
+
+    async fn rpc(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        CastWrapper::rpc(&self.endpoint, method, params)
+            .await
+            .map_err(|e| format!("{} failed: {}", method, e))
+    }
+}
+
+#[derive(Default)]
+pub struct DevnetState {
+    instances: HashMap<String, Devnet>,
+    // Devnets being spawned, with the port they asked for
+    starting: HashMap<String, Option<u16>>,
+    // User-configured Anvil locations, searched before PATH and install dirs
+    anvil_paths: Vec<PathBuf>,
+    anvil_paths_file: Option<PathBuf>,
+    pid_file: Option<PathBuf>,
+}
+
+impl DevnetState {
+    // Remove devnets whose Anvil process has exited, returning the ones
+    // configured to auto-restart so they can be relaunched without holding
+    // the state lock
+    async fn reap_exited(&mut self) -> Vec<DevnetRestart> {
+        let mut exited = Vec::new();
+        for (id, devnet) in self.instances.iter_mut() {
+            match devnet.instance.child_mut().try_wait() {
+                Ok(Some(status)) => exited.push((id.clone(), status.code())),
+                Ok(None) => {}
+                Err(e) => error!("Failed to check devnet {} status: {}", id, e),
+            }
+        }
+
+        let mut restarts = Vec::new();
+        for (id, exit_code) in exited {
+            let Some(devnet) = self.instances.remove(&id) else {
+                continue;
+            };
+            self.record_pids();
+            warn!("Devnet {} exited with code {:?}", id, exit_code);
+
+            let restarting = devnet.config.auto_restart && devnet.restarts < MAX_DEVNET_RESTARTS;
+            let logs = devnet.logs().await;
+            let logs = logs[logs.len().saturating_sub(EXIT_LOG_LINES)..].to_vec();
+            let _ = devnet.window.emit(
+                "devnet-exited",
+                DevnetExit {
+                    id: id.clone(),
+                    exit_code,
+                    logs,
+                    restarting,
+                },
+            );
+            if !restarting {
+                continue;
+            }
+
+            // Keep the RPC URL stable across restarts
+            let mut config = devnet.config.clone();
+            config.port = Some(devnet.instance.port());
+            restarts.push(DevnetRestart {
+                id,
+                launch: devnet.launch.clone(),
+                config,
+                restarts: devnet.restarts + 1,
+                window: devnet.window.clone(),
+            });
+        }
+        restarts
+    }
+
+    pub fn stop(&mut self, id: &str) -> Result<(), String> {
+        // AnvilInstance kills the child process when dropped
+        match self.instances.remove(id) {
+            Some(_) => {
+                self.record_pids();
+                Ok(())
+            }
+            None => Err(format!("Devnet {} is not running", id)),
+        }
+    }
+
+    // Stop every devnet, dumping state first for those configured to
+    pub async fn shutdown(&mut self) {
+        for (id, devnet) in self.instances.drain() {
+            if let Some(name) = &devnet.config.dump_state_on_exit {
+                let app = devnet.window.app_handle();
+                match devnet.client().dump_state(&app, name).await {
+                    Ok(_) => info!("Saved devnet {} state as {}", id, name),
+                    Err(e) => error!("Failed to save devnet {} state: {}", id, e),
+                }
+            }
+            info!("Stopping devnet {}", id);
+        }
+        self.record_pids();
+    }
+
+    pub fn set_pid_file(&mut self, path: PathBuf) {
+        self.pid_file = Some(path);
+        self.record_pids();
+    }
+
+    fn record_pids(&self) {
+        let Some(path) = &self.pid_file else {
+            return;
+        };
+        if self.instances.is_empty() {
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+
+        let processes: HashMap<&String, DevnetProcess> = self
+            .instances
+            .iter()
+            .map(|(id, devnet)| {
+                let process = DevnetProcess {
+                    pid: devnet.instance.child().id(),
+                    port: devnet.instance.port(),
+                };
+                (id, process)
+            })
+            .collect();
+        let result = serde_json::to_string_pretty(&processes)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("Failed to write devnet pid file {:?}: {}", path, e);
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Result<&Devnet, String> {
+        self.instances
+            .get(id)
+            .ok_or_else(|| format!("Devnet {} is not running", id))
+    }
+
+    pub fn is_running(&self, id: &str) -> bool {
+        self.instances.contains_key(id)
+    }
+
+    pub fn list(&self) -> Vec<DevnetInfo> {
+        let mut devnets: Vec<DevnetInfo> = self
+            .instances
+            .iter()
+            .map(|(id, devnet)| devnet.info(id))
+            .collect();
+        devnets.sort_by(|a, b| a.id.cmp(&b.id));
+        devnets
+    }
+
+    // Load `anvil-paths.json` from the app config dir
+    pub fn load_anvil_paths(&mut self, file: PathBuf) {
+        let mut writable = true;
+        if let Ok(content) = std::fs::read_to_string(&file) {
+            match serde_json::from_str(&content) {
+                Ok(paths) => self.anvil_paths = paths,
+                Err(e) => {
+                    warn!("Unreadable Anvil paths {:?}: {}", file, e);
+                    // Don't save over paths we couldn't back up
+                    writable = set_aside(&file);
+                }
+            }
+        }
+        self.anvil_paths_file = writable.then_some(file);
+    }
+
+    pub fn set_anvil_paths(&mut self, paths: Vec<PathBuf>) -> Result<(), String> {
+        self.anvil_paths = paths;
+        let Some(file) = &self.anvil_paths_file else {
+            return Ok(());
+        };
+        let content = serde_json::to_string_pretty(&self.anvil_paths).map_err(|e| e.to_string())?;
+        write_atomic(file, &content).map_err(|e| e.to_string())
+    }
+
+    pub fn find_anvil(&self) -> Result<AnvilBinary, AnvilError> {
+        find_anvil(&self.anvil_paths)
+    }
+
+    fn ensure_available(&self, id: &str, port: Option<u16>) -> Result<(), String> {
+        if self.instances.contains_key(id) {
+            return Err(format!("Devnet {} is already running", id));
+        }
+        if self.starting.contains_key(id) {
+            return Err(format!("Devnet {} is already starting", id));
+        }
+        if let Some(port) = port {
+            let used_by = self
+                .instances
+                .iter()
+                .find(|(_, devnet)| devnet.instance.port() == port)
+                .map(|(other, _)| other)
+                .or_else(|| {
+                    self.starting
+                        .iter()
+                        .find(|(_, starting)| **starting == Some(port))
+                        .map(|(other, _)| other)
+                });
+            if let Some(other) = used_by {
+                return Err(format!("Port {} is already used by devnet {}", port, other));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn watch_devnets(state: Arc<Mutex<DevnetState>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let restarts = state.lock().await.reap_exited().await;
+            for restart in restarts {
+                restart_devnet(&state, restart).await;
+            }
+        }
+    });
+}
+
+pub async fn start_devnet(
+    state: &Mutex<DevnetState>,
+    id: String,
+    config: DevnetConfig,
+    window: tauri::Window,
+) -> Result<DevnetInfo, String> {
+    launch_devnet(state, id, DevnetLaunch::Local, config, window, 0).await
+}
+
+pub async fn fork_devnet(
+    state: &Mutex<DevnetState>,
+    id: String,
+    url: String,
+    options: ForkOptions,
+    config: DevnetConfig,
+    window: tauri::Window,
+) -> Result<DevnetInfo, String> {
+    let launch = DevnetLaunch::Fork { url, options };
+    launch_devnet(state, id, launch, config, window, 0).await
+}
+
+pub async fn launch_profile(
+    state: &Mutex<DevnetState>,
+    id: String,
+    profile: DevnetProfile,
+    window: tauri::Window,
+) -> Result<DevnetInfo, String> {
+    info!("Launching devnet profile {} as {}", profile.name, id);
+    match profile.fork {
+        Some(fork) => fork_devnet(state, id, fork.url, fork.options, profile.config, window).await,
+        None => start_devnet(state, id, profile.config, window).await,
+    }
+}
+
+// Relaunch an exited devnet
+async fn restart_devnet(state: &Mutex<DevnetState>, restart: DevnetRestart) {
+    let DevnetRestart {
+        id,
+        launch,
+        config,
+        restarts,
+        window,
+    } = restart;
+    info!("Restarting devnet {} (attempt {})", id, restarts);
+
+    match launch_devnet(state, id.clone(), launch, config, window.clone(), restarts).await {
+        Ok(info) => {
+            let _ = window.emit("devnet-restarted", info);
+        }
+        Err(e) => {
+            error!("Failed to restart devnet {}: {}", id, e);
+            let _ = window.emit("devnet-restart-failed", DevnetRestartError { id, error: e });
+        }
+    }
+}
+
+// Spawning Anvil, forking and loading state can take a while, so the state
+// is only locked to reserve the id and port and to insert the result
+async fn launch_devnet(
+    state: &Mutex<DevnetState>,
+    id: String,
+    launch: DevnetLaunch,
+    config: DevnetConfig,
+    window: tauri::Window,
+    restarts: u32,
+) -> Result<DevnetInfo, String> {
+    let anvil_paths = {
+        let mut state = state.lock().await;
+        state.ensure_available(&id, config.port)?;
+        state.starting.insert(id.clone(), config.port);
+        state.anvil_paths.clone()
+    };
+    let result = match find_anvil(&anvil_paths) {
+        Ok(binary) => {
+            let path = binary.path().to_path_buf();
+            spawn_devnet(path, &id, launch, config, window, restarts > 0).await
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    let mut state = state.lock().await;
+    state.starting.remove(&id);
+    let mut devnet = result?;
+    // Anvil may have picked a port another devnet took in the meantime;
+    // dropping the new one stops its Anvil again
+    state.ensure_available(&id, Some(devnet.instance.port()))?;
+    devnet.restarts = restarts;
+    let info = devnet.info(&id);
+    state.instances.insert(id, devnet);
+    state.record_pids();
+    Ok(info)
+}
+
+// Spawn Anvil for a devnet and bring it to its configured starting state
+async fn spawn_devnet(
+    anvil_path: PathBuf,
+    id: &str,
+    launch: DevnetLaunch,
+    config: DevnetConfig,
+    window: tauri::Window,
+    restart: bool,
+) -> Result<Devnet, String> {
+    let app = window.app_handle();
+
+    let mut anvil = Anvil::at(anvil_path);
+    if let DevnetLaunch::Fork { url, options } = &launch {
+        anvil = options.apply(url, anvil).await?;
+    }
+    anvil = config.apply(anvil);
+    if let Some(interval) = config.state_interval {
+        let path = autosave_path(&app, id)?;
+        if !restart {
+            // A fresh launch must not pick up state from an earlier session
+            let _ = std::fs::remove_file(&path);
+        }
+        anvil = anvil.args([
+            "--state".to_string(),
+            path.to_string_lossy().into_owned(),
+            "--state-interval".to_string(),
+            interval.to_string(),
+        ]);
+    }
+    let instance = spawn_anvil(anvil, &config)?;
+
+    // Without an explicit chain id a fork inherits the remote one
+    let chain_id = match &launch {
+        DevnetLaunch::Fork { .. } if config.chain_id.is_none() => {
+            CastWrapper::chain_id(&instance.endpoint())
+                .await
+                .map_err(|e| e.to_string())?
+                .parse()
+                .map_err(|e| format!("Invalid chain id from forked devnet: {}", e))?
+        }
+        _ => instance.chain_id(),
+    };
+
+    match &launch {
+        DevnetLaunch::Local => info!("Started devnet {} on port {}", id, instance.port()),
+        DevnetLaunch::Fork { url, .. } => info!(
+            "Started devnet {} forked from {} on port {}",
+            id,
+            url,
+            instance.port()
+        ),
+    }
+
+    // On restart the autosaved state supersedes the configured dump
+    let load_state = match config.state_interval {
+        Some(_) if restart => None,
+        _ => config.load_state.clone(),
+    };
+
+    let devnet = Devnet::new(id, instance, chain_id, launch, config, window);
+    if let Some(name) = load_state {
+        // Dropping the devnet on failure stops the freshly spawned Anvil
+        devnet.client().load_state(&app, &name).await?;
+    }
+    Ok(devnet)
+}
+
+// Kill Anvil processes left behind by a previous run that didn't shut down
+// cleanly. Pids are only trusted while they still belong to an anvil binary.
+pub fn cleanup_stale_devnets(pid_file: &Path) {
+    let Ok(content) = std::fs::read_to_string(pid_file) else {
+        return;
+    };
+    let processes: HashMap<String, DevnetProcess> = match serde_json::from_str(&content) {
+        Ok(processes) => processes,
+        Err(e) => {
+            warn!("Ignoring invalid devnet pid file {:?}: {}", pid_file, e);
+            HashMap::new()
+        }
+    };
+
+    for (id, process) in processes {
+        if !is_anvil_process(process.pid) {
+            continue;
+        }
+        info!(
+            "Stopping stale devnet {} (pid {}, port {})",
+            id, process.pid, process.port
+        );
+        if let Err(e) = kill_process(process.pid) {
+            error!("Failed to stop stale devnet {}: {}", id, e);
+        }
+    }
+    let _ = std::fs::remove_file(pid_file);
+}
+
+#[cfg(unix)]
+fn is_anvil_process(pid: u32) -> bool {
+    Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .map(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).contains("anvil")
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn is_anvil_process(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .to_lowercase()
+                .contains("anvil")
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(unix)]
+fn kill_process(pid: u32) -> Result<(), String> {
+    let status = Command::new("kill")
+        .arg(pid.to_string())
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("kill exited with {}", status))
+    }
+}
+
+#[cfg(windows)]
+fn kill_process(pid: u32) -> Result<(), String> {
+    let status = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/F"])
+        .status()
+        .map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("taskkill exited with {}", status))
+    }
+}
+
+pub fn list_saved_states(app: &tauri::AppHandle) -> Result<Vec<SavedDevnetState>, String> {
+    let dir = saved_states_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut states = Vec::new();
+    for entry in std::fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.extension().is_none_or(|ext| ext != "state") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            states.push(saved_state(name, &path)?);
+        }
+    }
+    states.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(states)
+}
+
+pub fn delete_saved_state(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    std::fs::remove_file(saved_state_path(app, name)?)
+        .map_err(|e| format!("Failed to delete saved state {}: {}", name, e))
+}
+
+pub fn list_profiles(app: &tauri::AppHandle) -> Result<Vec<DevnetProfile>, String> {
+    let dir = profiles_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in std::fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        match read_profile(&path) {
+            Ok(profile) => profiles.push(profile),
+            Err(e) => warn!("Skipping devnet profile {:?}: {}", path, e),
+        }
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+pub fn get_profile(app: &tauri::AppHandle, name: &str) -> Result<DevnetProfile, String> {
+    let path = profile_path(app, name)?;
+    if !path.exists() {
+        return Err(format!("Devnet profile {} not found", name));
+    }
+    read_profile(&path)
+}
+
+pub fn create_profile(app: &tauri::AppHandle, profile: &DevnetProfile) -> Result<(), String> {
+    let path = profile_path(app, &profile.name)?;
+    if path.exists() {
+        return Err(format!("Devnet profile {} already exists", profile.name));
+    }
+    write_profile(&path, profile)
+}
+
+// Renames the profile when `profile.name` differs from `name`
+pub fn update_profile(
+    app: &tauri::AppHandle,
+    name: &str,
+    profile: &DevnetProfile,
+) -> Result<(), String> {
+    let old_path = profile_path(app, name)?;
+    if !old_path.exists() {
+        return Err(format!("Devnet profile {} not found", name));
+    }
+
+    let path = profile_path(app, &profile.name)?;
+    if path != old_path && path.exists() {
+        return Err(format!("Devnet profile {} already exists", profile.name));
+    }
+    write_profile(&path, profile)?;
+    if path != old_path {
+        std::fs::remove_file(&old_path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn delete_profile(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    std::fs::remove_file(profile_path(app, name)?)
+        .map_err(|e| format!("Failed to delete devnet profile {}: {}", name, e))
+}
+
+fn profiles_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path_resolver()
+        .app_config_dir()
+        .ok_or("Failed to resolve app config directory")?;
+    Ok(dir.join("devnet-profiles"))
+}
+
+fn profile_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    validate_file_name(name)?;
+    Ok(profiles_dir(app)?.join(format!("{}.json", name)))
+}
+
+fn read_profile(path: &Path) -> Result<DevnetProfile, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid devnet profile: {}", e))
+}
+
+fn write_profile(path: &Path, profile: &DevnetProfile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(profile).map_err(|e| e.to_string())?;
+    write_atomic(path, &content).map_err(|e| e.to_string())
+}
+
+fn saved_states_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or("Failed to resolve app data directory")?;
+    Ok(dir.join("devnet-states"))
+}
+
+// Saved states hold the hex-encoded dump returned by `anvil_dumpState`
+fn saved_state_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    validate_file_name(name)?;
+    Ok(saved_states_dir(app)?.join(format!("{}.state", name)))
+}
+
+// Anvil's own periodic dumps, kept apart from the user's saved states since
+// they use the plain JSON format of `--dump-state`
+fn autosave_path(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    validate_file_name(id)?;
+    let dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or("Failed to resolve app data directory")?
+        .join("devnet-autosave");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("{}.json", id)))
+}
+
+fn validate_file_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid name: {}", name))
+    }
+}
+
+fn saved_state(name: &str, path: &Path) -> Result<SavedDevnetState, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs());
+
+    Ok(SavedDevnetState {
+        name: name.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        size: metadata.len(),
+        modified,
+    })
+}
+
+fn parse_address(address: &str) -> Result<Address, String> {
+    Address::from_str(address).map_err(|e| format!("Invalid address {}: {}", address, e))
+}
+
+// Accepts decimal or 0x-prefixed hex
+fn parse_u256(value: &str) -> Result<U256, String> {
+    U256::from_str(value).map_err(|e| format!("Invalid number {}: {}", value, e))
+}
+
+fn spawn_anvil(anvil: Anvil, config: &DevnetConfig) -> Result<AnvilInstance, String> {
+    if config.no_mining && config.block_time.is_some() {
+        return Err("Block time cannot be combined with no-mining".to_string());
+    }
+
+    anvil
+        .keep_stdout()
+        .try_spawn()
+        .map_err(|e| format!("Failed to spawn Anvil instance: {}", e))
+}
+
+async fn resolve_block_hash(url: &str, block_hash: &str) -> Result<u64, String> {
+    let block = BlockId::from_str(block_hash).map_err(|e| format!("Invalid block hash: {}", e))?;
+    let number = CastWrapper::block(url, Some(block), false, Some("number".to_string()), false)
+        .await
+        .map_err(|e| format!("Failed to resolve fork block {}: {}", block_hash, e))?;
+    let number = number.trim();
+
+    match number.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => number.parse(),
+    }
+    .map_err(|e| format!("Invalid block number {} for {}: {}", number, block_hash, e))
+}
+
+// Forward Anvil's stdout into the log buffer and the `devnet-log` event.
+// Alloy inherits stderr for the child, so stdout is the only stream we can read.
+fn capture_devnet_logs(
+    id: String,
+    instance: &mut AnvilInstance,
+    logs: Arc<Mutex<VecDeque<String>>>,
+    window: tauri::Window,
+) {
+    let Some(stdout) = instance.child_mut().stdout.take() else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            {
+                let mut logs = logs.blocking_lock();
+                if logs.len() == MAX_DEVNET_LOGS {
+                    logs.pop_front();
+                }
+                logs.push_back(line.clone());
+            }
+            let _ = window.emit(
+                "devnet-log",
+                DevnetLog {
+                    id: id.clone(),
+                    line,
+                },
+            );
+        }
+    });
+}
@@ -0,0 +1,188 @@
+use crate::cast_wrapper::CastWrapper;
+use crate::persist::{set_aside, write_atomic};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use std::time::Instant;
+
+// A named RPC endpoint, so commands can take `mainnet` instead of a URL
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RpcEndpoint {
+    pub name: String,
+    pub url: String,
+    // Expected chain id, checked when the endpoint is tested
+    pub chain_id: Option<u64>,
+    // Extra HTTP headers, e.g. `Authorization` for keyed providers
+    pub headers: BTreeMap<String, String>,
+    // Compute units per second, the unit foundry's retry layer throttles by
+    pub rate_limit: Option<u64>,
+    // Request timeout in seconds
+    pub timeout: Option<u64>,
+    pub max_retries: Option<u32>,
+    // Milliseconds before the first retry, doubled on each attempt
+    pub initial_backoff: Option<u64>,
+}
+
+impl RpcEndpoint {
+    // Whether a provider built for `other` can serve this endpoint too
+    pub fn same_connection(&self, other: &RpcEndpoint) -> bool {
+        self.url == other.url
+            && self.headers == other.headers
+            && self.rate_limit == other.rate_limit
+            && self.timeout == other.timeout
+            && self.max_retries == other.max_retries
+            && self.initial_backoff == other.initial_backoff
+    }
+
+    fn transport(&self) -> &'static str {
+        if self.url.starts_with("ws://") || self.url.starts_with("wss://") {
+            "ws"
+        } else if self.url.starts_with("http://") || self.url.starts_with("https://") {
+            "http"
+        } else {
+            "ipc"
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct EndpointStatus {
+    transport: &'static str,
+    chain_id: u64,
+    block_number: u64,
+    client_version: String,
+    latency_ms: u64,
+}
+
+#[derive(Default)]
+struct EndpointRegistry {
+    file: Option<PathBuf>,
+    endpoints: Vec<RpcEndpoint>,
+}
+
+fn registry() -> &'static RwLock<EndpointRegistry> {
+    static REGISTRY: OnceLock<RwLock<EndpointRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+// Load `rpc-endpoints.json` from the app config dir
+pub fn load_endpoints(file: PathBuf) {
+    let (endpoints, writable) = match std::fs::read_to_string(&file) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(endpoints) => (endpoints, true),
+            Err(e) => {
+                warn!("Unreadable RPC endpoints {:?}: {}", file, e);
+                // Don't save over endpoints we couldn't back up
+                (Vec::new(), set_aside(&file))
+            }
+        },
+        Err(_) => (Vec::new(), true),
+    };
+    let mut registry = registry().write().unwrap();
+    registry.file = writable.then_some(file);
+    registry.endpoints = endpoints;
+}
+
+pub fn list_endpoints() -> Vec<RpcEndpoint> {
+    registry().read().unwrap().endpoints.clone()
+}
+
+// Treat the argument as an endpoint name first and fall back to a plain URL
+pub fn resolve_endpoint(name_or_url: &str) -> RpcEndpoint {
+    let registry = registry().read().unwrap();
+    registry
+        .endpoints
+        .iter()
+        .find(|endpoint| endpoint.name == name_or_url)
+        .cloned()
+        .unwrap_or_else(|| RpcEndpoint {
+            url: name_or_url.to_string(),
+            ..Default::default()
+        })
+}
+
+// Add an endpoint, replacing any existing one with the same name
+pub fn add_endpoint(endpoint: RpcEndpoint) -> Result<(), String> {
+    if endpoint.name.trim().is_empty() {
+        return Err("Endpoint name cannot be empty".to_string());
+    }
+    if endpoint.name.contains("://") {
+        return Err("Endpoint names cannot look like URLs".to_string());
+    }
+    if endpoint.url.trim().is_empty() {
+        return Err("Endpoint URL cannot be empty".to_string());
+    }
+
+    let mut registry = registry().write().unwrap();
+    let name = endpoint.name.clone();
+    match registry.endpoints.iter_mut().find(|e| e.name == name) {
+        Some(existing) => *existing = endpoint,
+        None => registry.endpoints.push(endpoint),
+    }
+    registry.save()
+}
+
+pub fn remove_endpoint(name: &str) -> Result<(), String> {
+    let mut registry = registry().write().unwrap();
+    let index = registry
+        .endpoints
+        .iter()
+        .position(|endpoint| endpoint.name == name)
+        .ok_or_else(|| format!("Unknown RPC endpoint: {}", name))?;
+    registry.endpoints.remove(index);
+    registry.save()
+}
+
+// Check that an endpoint answers and serves the chain it's configured for
+pub async fn test_endpoint(name_or_url: &str) -> Result<EndpointStatus, String> {
+    let endpoint = resolve_endpoint(name_or_url);
+    let started = Instant::now();
+    let chain_id = CastWrapper::chain_id(name_or_url)
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", endpoint.url, e))?
+        .parse::<u64>()
+        .map_err(|e| e.to_string())?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    if let Some(expected) = endpoint.chain_id {
+        if expected != chain_id {
+            return Err(format!(
+                "Endpoint serves chain {} but is configured for chain {}",
+                chain_id, expected
+            ));
+        }
+    }
+
+    let block_number = CastWrapper::block_number(name_or_url)
+        .await
+        .map_err(|e| e.to_string())?
+        .parse::<u64>()
+        .map_err(|e| e.to_string())?;
+    let client_version = CastWrapper::client(name_or_url)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(EndpointStatus {
+        transport: endpoint.transport(),
+        chain_id,
+        block_number,
+        client_version,
+        latency_ms,
+    })
+}
+
+impl EndpointRegistry {
+    fn save(&self) -> Result<(), String> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        let content = serde_json::to_string_pretty(&self.endpoints).map_err(|e| e.to_string())?;
+        write_atomic(file, &content).map_err(|e| {
+            error!("Failed to write RPC endpoints {:?}: {}", file, e);
+            e.to_string()
+        })
+    }
+}
@@ -1,15 +1,36 @@
-use cryo_cli::{run, Args};
-use cryo_freeze::FreezeSummary;
+use crate::cast_wrapper::CastWrapper;
+use crate::datasets::{dataset_catalog, DatasetSchema};
+use crate::endpoints::{resolve_endpoint, RpcEndpoint};
+use crate::manifest::{IndexerRun, RunFilter, RunManifest};
+use crate::validation::{validate_options, FieldError};
+use cryo_cli::{parse_args, Args};
+use cryo_freeze::{freeze, BlockChunk, ChunkData, FreezeSummary, Partition};
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::broadcast;
+use tokio::sync::oneshot;
 use tokio::sync::Mutex;
+use tokio::sync::Notify;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_POLL_INTERVAL: u64 = 12;
+// Cryo's retry defaults. A named endpoint's own retry settings are used for
+// jobs that leave these unchanged.
+const CRYO_MAX_RETRIES: u32 = 5;
+const CRYO_INITIAL_BACKOFF: u64 = 500;
+// How long a stopped run gets to finish writing the chunks it's on
+const CHUNK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IndexerState {
     indexing_progress: f32,
+    // Latest progress update of any job
+    progress: Option<IndexerProgress>,
+    max_concurrent_jobs: usize,
     log_messages: Vec<String>,
     available_datasets: Vec<String>,
     selected_dataset: Option<String>,
@@ -18,7 +39,7 @@ pub struct IndexerState {
     indexed_files: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IndexerOptions {
     // Basic options
@@ -84,128 +105,914 @@ pub struct IndexerOptions {
     pub event_signature: Option<String>,
     pub inner_request_size: u64,
     pub js_tracer: Option<String>,
+
+    // Follow options
+    #[serde(default)]
+    pub follow: bool,
+    // Seconds between polls for new blocks once following
+    #[serde(default)]
+    pub poll_interval: Option<u64>,
+}
+
+// Chunk-level progress of a cryo run. While running, `completed_chunks`
+// counts every processed chunk and the row, skip and error counts are None;
+// they're filled in from cryo's summary once the run finishes. Throughput is
+// reported as `chunks_per_second`, there is no requests-per-second figure.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerProgress {
+    job_id: String,
+    status: JobStatus,
+    datasets: Vec<String>,
+    completed_chunks: usize,
+    skipped_chunks: Option<usize>,
+    errored_chunks: Option<usize>,
+    total_chunks: usize,
+    rows_written: Option<u64>,
+    elapsed_secs: f64,
+    eta_secs: Option<f64>,
+    chunks_per_second: f64,
+    // Last block written while following the chain head
+    indexed_through: Option<u64>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    #[default]
+    Queued,
+    Running,
+    // Backfill is done and new blocks are indexed as they arrive
+    Following,
+    Paused,
+    Cancelled,
+    Completed,
+    Failed,
+}
+
+type JobResult = Result<CompactFreezeSummary, String>;
+
+// A queued cryo run. Cryo can't suspend a run, so pausing stops it like a
+// cancel but keeps the job around to be resumed; chunks that were already
+// written are skipped when it runs again without `overwrite`.
+struct IndexerJob {
+    id: String,
+    path: PathBuf,
+    datasets: Vec<String>,
+    options: IndexerOptions,
+    status: JobStatus,
+    progress: Option<IndexerProgress>,
+    summary: Option<CompactFreezeSummary>,
+    error: Option<String>,
+    // Next block to index once the job is following the chain head
+    follow_from: Option<u64>,
+    stop: Option<oneshot::Sender<JobStatus>>,
+    // Callers waiting for the job to complete, see `start_indexing`
+    waiters: Vec<oneshot::Sender<JobResult>>,
+    // Removed while running; dropped once it has stopped, so it keeps its
+    // slot until then
+    removed: bool,
+}
+
+impl IndexerJob {
+    fn info(&self) -> IndexerJobInfo {
+        IndexerJobInfo {
+            id: self.id.clone(),
+            path: self.path.to_string_lossy().into_owned(),
+            datasets: self.datasets.clone(),
+            status: self.status,
+            progress: self.progress.clone(),
+            summary: self.summary.clone(),
+            error: self.error.clone(),
+        }
+    }
+
+    fn notify_waiters(&mut self, result: &JobResult) {
+        for waiter in self.waiters.drain(..) {
+            let _ = waiter.send(result.clone());
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerJobInfo {
+    id: String,
+    path: String,
+    datasets: Vec<String>,
+    status: JobStatus,
+    progress: Option<IndexerProgress>,
+    summary: Option<CompactFreezeSummary>,
+    error: Option<String>,
+}
+
+type IndexerJobs = Arc<Mutex<Vec<IndexerJob>>>;
+
 pub struct IndexerTool {
     state: Arc<Mutex<IndexerState>>,
+    catalog: Vec<DatasetSchema>,
+    jobs: IndexerJobs,
+    next_job_id: AtomicU64,
+    // Wakes the scheduler when a job is queued or a running one ends
+    scheduler: Notify,
     log_sender: broadcast::Sender<String>,
+    progress_sender: broadcast::Sender<IndexerProgress>,
+    files_sender: broadcast::Sender<Vec<String>>,
+    manifest: Mutex<RunManifest>,
 }
 
 impl IndexerTool {
     pub fn new() -> Self {
         let (log_sender, _) = broadcast::channel(100);
+        let (progress_sender, _) = broadcast::channel(100);
+        let (files_sender, _) = broadcast::channel(100);
+        let catalog = dataset_catalog();
 
         let state = Arc::new(Mutex::new(IndexerState {
             indexing_progress: 0.0,
+            progress: None,
+            max_concurrent_jobs: 1,
             log_messages: Vec::new(),
-            available_datasets: vec![
-                "address_appearances".to_string(),
-                "balance_diffs".to_string(),
-                "balance_reads".to_string(),
-                "balances".to_string(),
-                "blocks".to_string(),
-                "code_diffs".to_string(),
-                "code_reads".to_string(),
-                "codes".to_string(),
-                "contracts".to_string(),
-                "erc20_balances".to_string(),
-                "erc20_metadata".to_string(),
-                "erc20_supplies".to_string(),
-                "erc20_transfers".to_string(),
-                "erc721_metadata".to_string(),
-                "erc721_transfers".to_string(),
-                "eth_calls".to_string(),
-                "four_byte_counts".to_string(),
-                "geth_calls".to_string(),
-                "geth_code_diffs".to_string(),
-                "geth_balance_diffs".to_string(),
-                "geth_storage_diffs".to_string(),
-                "geth_nonce_diffs".to_string(),
-                "geth_opcodes".to_string(),
-                "javascript_traces".to_string(),
-                "logs".to_string(),
-                "native_transfers".to_string(),
-                "nonce_diffs".to_string(),
-                "nonce_reads".to_string(),
-                "nonces".to_string(),
-                "slots".to_string(),
-                "storage_diffs".to_string(),
-                "storage_reads".to_string(),
-                "traces".to_string(),
-                "trace_calls".to_string(),
-                "transactions".to_string(),
-                "vm_traces".to_string(),
-            ],
+            available_datasets: catalog
+                .iter()
+                .map(|schema| schema.name().to_string())
+                .collect(),
             selected_dataset: None,
             summary: None,
             indexed_dirs: Vec::new(),
             indexed_files: Vec::new(),
         }));
 
-        IndexerTool { state, log_sender }
+        IndexerTool {
+            state,
+            catalog,
+            jobs: Arc::new(Mutex::new(Vec::new())),
+            next_job_id: AtomicU64::new(1),
+            scheduler: Notify::new(),
+            log_sender,
+            progress_sender,
+            files_sender,
+            manifest: Mutex::new(RunManifest::default()),
+        }
+    }
+
+    // Load past runs so the files they wrote show up as indexed
+    pub async fn load_manifest(&self, file: PathBuf) {
+        let manifest = RunManifest::load(file);
+        let mut state = self.state.lock().await;
+        for run in manifest.runs() {
+            if !state.indexed_dirs.contains(&run.path) {
+                state.indexed_dirs.push(run.path.clone());
+            }
+            state.indexed_files.extend(run.files.iter().cloned());
+        }
+        drop(state);
+        *self.manifest.lock().await = manifest;
+    }
+
+    pub fn start_scheduler(self: &Arc<Self>) {
+        let tool = self.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tool.scheduler.notified().await;
+                tool.start_queued_jobs().await;
+            }
+        });
     }
 
+    // Queue a single-dataset job and wait for it to finish, or for its
+    // backfill to finish if it follows the chain head
     pub async fn start_indexing(
         &self,
         path: PathBuf,
         dataset: String,
         options: IndexerOptions,
-    ) -> Result<CompactFreezeSummary, String> {
-        info!(target: "cryo", "Starting indexing for dataset: {} at path: {:?}", dataset, path);
+    ) -> JobResult {
+        let (sender, receiver) = oneshot::channel();
+        let job_id = self
+            .enqueue(path, vec![dataset], options, Some(sender))
+            .await?;
+        receiver
+            .await
+            .map_err(|_| format!("Indexing job {} was removed", job_id))?
+    }
+
+    pub async fn enqueue_indexing(
+        &self,
+        path: PathBuf,
+        datasets: Vec<String>,
+        options: IndexerOptions,
+    ) -> Result<String, String> {
+        self.enqueue(path, datasets, options, None).await
+    }
+
+    async fn enqueue(
+        &self,
+        path: PathBuf,
+        datasets: Vec<String>,
+        options: IndexerOptions,
+        waiter: Option<oneshot::Sender<JobResult>>,
+    ) -> Result<String, String> {
+        let errors = self.validate_options(&datasets, &options);
+        if !errors.is_empty() {
+            let messages: Vec<String> = errors.iter().map(FieldError::to_string).collect();
+            return Err(format!("Invalid indexing options: {}", messages.join("; ")));
+        }
+
+        let job_id = format!("job-{}", self.next_job_id.fetch_add(1, Ordering::Relaxed));
+        info!(target: "cryo", "Queueing indexing job {} for datasets: {:?} at path: {:?}", job_id, datasets, path);
 
         let mut state = self.state.lock().await;
-        state.selected_dataset = Some(dataset.clone());
-        state.indexing_progress = 0.0;
-        state
-            .log_messages
-            .push(format!("Starting indexing for dataset: {}", dataset));
         state.indexed_dirs.push(path.to_string_lossy().into_owned());
         drop(state);
 
-        let args = self.create_args(&path, &dataset, &options)?;
+        self.log(format!(
+            "Queued indexing job {} for datasets: {}",
+            job_id,
+            datasets.join(", ")
+        ))
+        .await;
+        self.jobs.lock().await.push(IndexerJob {
+            id: job_id.clone(),
+            path,
+            datasets,
+            options,
+            status: JobStatus::Queued,
+            progress: None,
+            summary: None,
+            error: None,
+            follow_from: None,
+            stop: None,
+            waiters: waiter.into_iter().collect(),
+            removed: false,
+        });
+        self.scheduler.notify_one();
+        Ok(job_id)
+    }
+
+    // Temp files can't be told apart by job, so they're left alone while
+    // another job writes to the same directory. The jobs lock is held
+    // throughout so no job starts there mid-cleanup.
+    async fn remove_partial_chunks(&self, job_id: &str, path: &Path, subdirs: &[String]) {
+        let jobs = self.jobs.lock().await;
+        let shared = jobs.iter().any(|job| {
+            job.id != job_id
+                && job.path == path
+                && matches!(job.status, JobStatus::Running | JobStatus::Following)
+        });
+        if shared {
+            info!(target: "cryo", "Keeping partial chunks in {:?}, another job is writing there", path);
+            return;
+        }
+        remove_tmp_files(path, subdirs);
+    }
+
+    async fn start_queued_jobs(self: &Arc<Self>) {
+        let limit = self.state.lock().await.max_concurrent_jobs.max(1);
+        let mut jobs = self.jobs.lock().await;
+        let mut running = jobs
+            .iter()
+            .filter(|job| job.status == JobStatus::Running)
+            .count();
+
+        for job in jobs.iter_mut() {
+            if running >= limit {
+                break;
+            }
+            if job.status != JobStatus::Queued {
+                continue;
+            }
+            // Installed before spawning so a stop requested in the meantime
+            // isn't lost
+            let (stop_sender, stop_receiver) = oneshot::channel();
+            job.stop = Some(stop_sender);
+            job.status = JobStatus::Running;
+            running += 1;
+
+            let tool = self.clone();
+            let job_id = job.id.clone();
+            tauri::async_runtime::spawn(async move {
+                tool.run_job(&job_id, stop_receiver).await;
+                tool.scheduler.notify_one();
+            });
+        }
+    }
+
+    pub async fn list_jobs(&self) -> Vec<IndexerJobInfo> {
+        self.jobs
+            .lock()
+            .await
+            .iter()
+            .filter(|job| !job.removed)
+            .map(IndexerJob::info)
+            .collect()
+    }
+
+    pub async fn get_job(&self, job_id: &str) -> Result<IndexerJobInfo, String> {
+        let jobs = self.jobs.lock().await;
+        find_job(&jobs, job_id).map(IndexerJob::info)
+    }
+
+    // Queue a new job that runs only the chunks that errored in a finished
+    // job, with the same options
+    pub async fn retry_failed_chunks(&self, job_id: &str) -> Result<String, String> {
+        let jobs = self.jobs.lock().await;
+        let job = find_job(&jobs, job_id)?;
+        if !matches!(
+            job.status,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        ) {
+            return Err(format!("Indexing job {} is still active", job_id));
+        }
+        let errored = job
+            .summary
+            .as_ref()
+            .map(|summary| summary.errored.as_slice())
+            .unwrap_or_default();
+        if errored.is_empty() {
+            return Err(format!("Indexing job {} has no failed chunks", job_id));
+        }
+        let blocks: Vec<String> = errored.iter().filter_map(ErroredChunk::blocks).collect();
+        if blocks.is_empty() {
+            return Err("None of the failed chunks have a block range to retry".to_string());
+        }
+        let skipped = errored.len() - blocks.len();
+
+        let path = job.path.clone();
+        let datasets = job.datasets.clone();
+        let mut options = job.options.clone();
+        options.blocks = Some(blocks);
+        options.timestamps = None;
+        options.follow = false;
+        drop(jobs);
+
+        if skipped > 0 {
+            self.log(format!(
+                "Skipping {} failed chunks of job {} without a block range",
+                skipped, job_id
+            ))
+            .await;
+        }
+        let retry_id = self.enqueue(path, datasets, options, None).await?;
+        self.log(format!(
+            "Retrying failed chunks of job {} as {}",
+            job_id, retry_id
+        ))
+        .await;
+        Ok(retry_id)
+    }
+
+    // Forget a job, cancelling it first if it's still queued or running
+    pub async fn remove_job(&self, job_id: &str) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().await;
+        let index = jobs
+            .iter()
+            .position(|job| job.id == job_id && !job.removed)
+            .ok_or_else(|| format!("Unknown indexing job: {}", job_id))?;
+        let job = &mut jobs[index];
+        job.notify_waiters(&Err(format!("Indexing job {} was removed", job_id)));
+        if matches!(job.status, JobStatus::Running | JobStatus::Following) {
+            job.removed = true;
+            if let Some(stop) = job.stop.take() {
+                let _ = stop.send(JobStatus::Cancelled);
+            }
+        } else {
+            jobs.remove(index);
+        }
+        drop(jobs);
+
+        self.log(format!("Removed indexing job {}", job_id)).await;
+        Ok(())
+    }
+
+    pub async fn set_max_concurrent_jobs(&self, limit: usize) -> Result<(), String> {
+        if limit == 0 {
+            return Err("At least one job must be allowed to run".to_string());
+        }
+        self.state.lock().await.max_concurrent_jobs = limit;
+        self.scheduler.notify_one();
+        Ok(())
+    }
+
+    pub async fn resume_indexing(&self, job_id: &str) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().await;
+        let job = find_job_mut(&mut jobs, job_id)?;
+        if job.status != JobStatus::Paused {
+            return Err(format!("Indexing job {} is not paused", job_id));
+        }
+        // Keep the chunks written before the pause
+        job.options.overwrite = false;
+        job.status = JobStatus::Queued;
+        drop(jobs);
+
+        info!(target: "cryo", "Resuming indexing job {}", job_id);
+        self.scheduler.notify_one();
+        Ok(())
+    }
+
+    pub async fn pause_indexing(&self, job_id: &str) -> Result<(), String> {
+        self.stop_job(job_id, JobStatus::Paused).await
+    }
+
+    pub async fn cancel_indexing(&self, job_id: &str) -> Result<(), String> {
+        self.stop_job(job_id, JobStatus::Cancelled).await
+    }
+
+    async fn stop_job(&self, job_id: &str, status: JobStatus) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().await;
+        let job = find_job_mut(&mut jobs, job_id)?;
+        match job.status {
+            JobStatus::Running | JobStatus::Following => {
+                if let Some(stop) = job.stop.take() {
+                    let _ = stop.send(status);
+                }
+                Ok(())
+            }
+            // Nothing is running yet, only the status changes
+            JobStatus::Queued | JobStatus::Paused => {
+                job.status = status;
+                if status == JobStatus::Cancelled {
+                    job.notify_waiters(&Err(format!("Indexing job {} was cancelled", job_id)));
+                }
+                Ok(())
+            }
+            _ => Err(format!("Indexing job {} has already finished", job_id)),
+        }
+    }
+
+    async fn run_job(&self, job_id: &str, mut stop_receiver: oneshot::Receiver<JobStatus>) {
+        let mut jobs = self.jobs.lock().await;
+        if take_removed(&mut jobs, job_id) {
+            return;
+        }
+        let Ok(job) = find_job_mut(&mut jobs, job_id) else {
+            return;
+        };
+        let path = job.path.clone();
+        let datasets = job.datasets.clone();
+        let options = job.options.clone();
+        let follow_from = job.follow_from;
+        let summary = job.summary.clone();
+        let args = self.create_args(&path, &datasets, &options);
+        drop(jobs);
+
+        let mut state = self.state.lock().await;
+        state.selected_dataset = datasets.first().cloned();
+        state.indexing_progress = 0.0;
+        state.progress = None;
+        drop(state);
+        self.log(format!(
+            "Starting indexing job {} for datasets: {}",
+            job_id,
+            datasets.join(", ")
+        ))
+        .await;
+
+        let args = match args {
+            Ok(args) => args,
+            Err(e) => return self.finish_job(job_id, Err(e), Duration::ZERO).await,
+        };
         debug!(target: "cryo", "Created args: {:?}", args);
+        let started = Instant::now();
 
-        // Run the indexing process
-        info!(target: "cryo", "Running indexing process");
-        let result = run(args).await;
+        // A resumed follower carries on from the last block it wrote instead
+        // of running the backfill again
+        let result = if let (Some(_), Some(summary)) = (follow_from, summary) {
+            Ok(Ok(summary))
+        } else {
+            let template = IndexerProgress {
+                job_id: job_id.to_string(),
+                status: JobStatus::Running,
+                datasets: datasets.clone(),
+                ..Default::default()
+            };
+            self.run_freeze(&args, template, &mut stop_receiver).await
+        };
+
+        let result = match result {
+            Ok(Ok(summary)) if options.follow => {
+                // Start right after the last block the backfill wrote, which
+                // may be before or past the head when it started
+                let next_block = match follow_from.or(summary.last_block.map(|block| block + 1)) {
+                    Some(next_block) => next_block,
+                    None => match safe_head(&options).await {
+                        Ok(head) => head + 1,
+                        Err(e) => return self.finish_job(job_id, Err(e), started.elapsed()).await,
+                    },
+                };
+                Err(self
+                    .follow_head(
+                        job_id,
+                        &datasets,
+                        &options,
+                        args,
+                        summary,
+                        next_block,
+                        started,
+                        &mut stop_receiver,
+                    )
+                    .await)
+            }
+            result => result,
+        };
 
         match result {
+            Ok(result) => self.finish_job(job_id, result, started.elapsed()).await,
+            Err(status) => {
+                info!(target: "cryo", "Indexing job {} stopped: {:?}", job_id, status);
+                self.remove_partial_chunks(job_id, &path, &options.subdirs)
+                    .await;
+                self.finish_stopped_job(job_id, status, started.elapsed())
+                    .await
+            }
+        }
+    }
+
+    // Same steps as `cryo_cli::run`, done here so we can watch the progress
+    // bar cryo advances after every chunk. Returns the requested status
+    // instead if the job is stopped first.
+    async fn run_freeze(
+        &self,
+        args: &Args,
+        template: IndexerProgress,
+        stop: &mut oneshot::Receiver<JobStatus>,
+    ) -> Result<JobResult, JobStatus> {
+        let (query, source, sink, env) = match parse_args(args).await {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                error!(target: "cryo", "Invalid indexing options: {}", e);
+                return Ok(Err(e.to_string()));
+            }
+        };
+        let chain_id = source.chain_id;
+        let source = Arc::new(source);
+        let env = env.set_start_time();
+
+        // Cryo spawns a task per chunk, which would keep running after the
+        // `freeze` future is dropped. Running it on a runtime of its own lets
+        // a stop shut those tasks down too.
+        let runtime = match tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("cryo")
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => return Ok(Err(format!("Failed to start indexing runtime: {}", e))),
+        };
+
+        let bar = env.bar.clone();
+        let state = self.state.clone();
+        let jobs = self.jobs.clone();
+        let progress_sender = self.progress_sender.clone();
+        let monitor = tokio::spawn(async move {
+            let Some(bar) = bar else { return };
+            let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+            loop {
+                interval.tick().await;
+                let eta = bar.eta().as_secs_f64();
+                let progress = IndexerProgress {
+                    completed_chunks: bar.position() as usize,
+                    total_chunks: bar.length().unwrap_or(0) as usize,
+                    elapsed_secs: bar.elapsed().as_secs_f64(),
+                    eta_secs: (bar.position() > 0).then_some(eta),
+                    chunks_per_second: bar.per_sec(),
+                    ..template.clone()
+                };
+                publish_progress(&state, &jobs, &progress_sender, progress).await;
+            }
+        });
+
+        // Run the indexing process until it finishes or the job is stopped
+        info!(target: "cryo", "Running indexing process");
+        let run = runtime.spawn(async move {
+            freeze(&query, &source, &sink, &env)
+                .await
+                .map_err(|e| e.to_string())
+        });
+        let result = tokio::select! {
+            result = run => Ok(match result {
+                Ok(result) => result,
+                Err(e) => Err(e.to_string()),
+            }),
+            status = &mut *stop => Err(status.unwrap_or(JobStatus::Cancelled)),
+        };
+        monitor.abort();
+        // Wait for chunks that are mid-write so none lands after the job is
+        // reported stopped, or while its partial chunks are cleaned up
+        let _ =
+            tokio::task::spawn_blocking(move || runtime.shutdown_timeout(CHUNK_SHUTDOWN_TIMEOUT))
+                .await;
+
+        Ok(match result? {
             Ok(Some(summary)) => {
-                let compact_summary = CompactFreezeSummary::from(summary);
-                let mut state = self.state.lock().await;
-                state.summary = Some(format!("{:?}", compact_summary));
-                state.indexing_progress = 100.0;
-                state
-                    .log_messages
-                    .push("Indexing completed successfully.".to_string());
-                Ok(compact_summary)
+                let mut summary = CompactFreezeSummary::from(summary);
+                summary.chain_id = Some(chain_id);
+                Ok(summary)
             }
             Ok(None) => {
-                let mut state = self.state.lock().await;
-                state
-                    .log_messages
-                    .push("Indexing completed, but no summary was produced.".to_string());
+                self.log("Indexing completed, but no summary was produced.".to_string())
+                    .await;
                 Err("No summary produced".to_string())
             }
             Err(e) => {
                 error!(target: "cryo", "Indexing error: {}", e);
-                let mut state = self.state.lock().await;
-                state.log_messages.push(format!("Error: {}", e));
-                Err(e.to_string())
+                Err(e)
+            }
+        })
+    }
+
+    // Poll for new blocks and index them in batches, staying `reorg_buffer`
+    // blocks behind the head, until the job is paused or cancelled
+    #[allow(clippy::too_many_arguments)]
+    async fn follow_head(
+        &self,
+        job_id: &str,
+        datasets: &[String],
+        options: &IndexerOptions,
+        mut args: Args,
+        mut summary: CompactFreezeSummary,
+        mut next_block: u64,
+        started: Instant,
+        stop: &mut oneshot::Receiver<JobStatus>,
+    ) -> JobStatus {
+        let poll_interval = Duration::from_secs(
+            options
+                .poll_interval
+                .unwrap_or(DEFAULT_POLL_INTERVAL)
+                .max(1),
+        );
+        // New batches only add chunks next to the ones already written
+        args.overwrite = false;
+
+        self.update_follower(job_id, datasets, &summary, next_block, started)
+            .await;
+        // Followers are idle most of the time, so they don't hold a job slot
+        self.scheduler.notify_one();
+        self.log(format!(
+            "Indexing job {} is following the chain head from block {}",
+            job_id, next_block
+        ))
+        .await;
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(poll_interval) => {}
+                status = &mut *stop => return status.unwrap_or(JobStatus::Cancelled),
+            }
+
+            let head = match safe_head(options).await {
+                Ok(head) => head,
+                Err(e) => {
+                    error!(target: "cryo", "Failed to fetch the chain head: {}", e);
+                    continue;
+                }
+            };
+            if head < next_block {
+                continue;
+            }
+
+            debug!(target: "cryo", "Indexing blocks {} to {}", next_block, head);
+            args.blocks = Some(vec![format!("{}:+{}", next_block, head - next_block + 1)]);
+            let template = IndexerProgress {
+                job_id: job_id.to_string(),
+                status: JobStatus::Following,
+                datasets: datasets.to_vec(),
+                indexed_through: next_block.checked_sub(1),
+                ..Default::default()
+            };
+            match self.run_freeze(&args, template, stop).await {
+                Err(status) => return status,
+                Ok(Ok(batch)) => {
+                    summary.merge(&batch);
+                    // With `align` cryo drops the trailing partial chunk, so
+                    // those blocks are asked for again on the next poll
+                    if let Some(last_block) = batch.last_block {
+                        next_block = next_block.max(last_block + 1);
+                    }
+                    self.update_follower(job_id, datasets, &summary, next_block, started)
+                        .await;
+                }
+                Ok(Err(e)) => {
+                    self.log(format!(
+                        "Failed to index blocks {} to {}: {}",
+                        next_block, head, e
+                    ))
+                    .await
+                }
+            }
+        }
+    }
+
+    // Record how far a follower got; callers of `start_indexing` are released
+    // once the backfill is done
+    async fn update_follower(
+        &self,
+        job_id: &str,
+        datasets: &[String],
+        summary: &CompactFreezeSummary,
+        next_block: u64,
+        started: Instant,
+    ) {
+        if let Ok(job) = find_job_mut(&mut self.jobs.lock().await, job_id) {
+            // The backfill just finished, its files can be queried already
+            if job.follow_from.is_none() {
+                let _ = self.files_sender.send(summary.files.clone());
+            }
+            job.status = JobStatus::Following;
+            job.follow_from = Some(next_block);
+            job.summary = Some(summary.clone());
+            job.notify_waiters(&Ok(summary.clone()));
+        }
+
+        let mut progress = summary.to_progress(job_id, datasets, started.elapsed());
+        progress.status = JobStatus::Following;
+        progress.eta_secs = None;
+        progress.indexed_through = next_block.checked_sub(1);
+        self.publish_progress(progress).await;
+    }
+
+    // Publish the last known progress of a stopped run as its final summary
+    async fn finish_stopped_job(&self, job_id: &str, status: JobStatus, elapsed: Duration) {
+        let mut jobs = self.jobs.lock().await;
+        if take_removed(&mut jobs, job_id) {
+            return;
+        }
+        let mut progress = IndexerProgress::default();
+        if let Ok(job) = find_job_mut(&mut jobs, job_id) {
+            job.status = status;
+            job.stop = None;
+            if let Some(last) = &job.progress {
+                progress = last.clone();
+            }
+            progress.datasets = job.datasets.clone();
+            // Paused jobs keep their waiters until they're resumed and finish
+            if status == JobStatus::Cancelled {
+                job.notify_waiters(&Err(format!("Indexing job {} was cancelled", job_id)));
+            }
+        }
+        drop(jobs);
+
+        progress.job_id = job_id.to_string();
+        progress.status = status;
+        progress.elapsed_secs = elapsed.as_secs_f64();
+        progress.eta_secs = None;
+        self.publish_progress(progress).await;
+        if status == JobStatus::Cancelled {
+            self.record_run(job_id, elapsed).await;
+        }
+
+        let action = if status == JobStatus::Paused {
+            "paused"
+        } else {
+            "cancelled"
+        };
+        self.log(format!("Indexing job {} {}", job_id, action))
+            .await;
+    }
+
+    async fn finish_job(&self, job_id: &str, result: JobResult, elapsed: Duration) {
+        let mut jobs = self.jobs.lock().await;
+        if take_removed(&mut jobs, job_id) {
+            return;
+        }
+        let Ok(job) = find_job_mut(&mut jobs, job_id) else {
+            return;
+        };
+        job.stop = None;
+        let progress = match &result {
+            Ok(summary) => {
+                job.status = JobStatus::Completed;
+                job.summary = Some(summary.clone());
+                summary.to_progress(job_id, &job.datasets, elapsed)
+            }
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e.clone());
+                let mut progress = job.progress.clone().unwrap_or_default();
+                progress.job_id = job_id.to_string();
+                progress.status = JobStatus::Failed;
+                progress.datasets = job.datasets.clone();
+                progress.elapsed_secs = elapsed.as_secs_f64();
+                progress.eta_secs = None;
+                progress
+            }
+        };
+        job.notify_waiters(&result);
+        drop(jobs);
+
+        if let Ok(summary) = &result {
+            self.state.lock().await.summary = Some(format!("{:?}", summary));
+        }
+        self.publish_progress(progress).await;
+        self.record_run(job_id, elapsed).await;
+        match result {
+            Ok(_) => {
+                self.log(format!("Indexing job {} completed successfully.", job_id))
+                    .await
+            }
+            Err(e) => self.log(format!("Error: {}", e)).await,
+        }
+    }
+
+    // Add a finished job to the run manifest
+    async fn record_run(&self, job_id: &str, elapsed: Duration) {
+        let jobs = self.jobs.lock().await;
+        let Ok(job) = find_job(&jobs, job_id) else {
+            return;
+        };
+        let started_at = SystemTime::now()
+            .checked_sub(elapsed)
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_secs());
+        let run = IndexerRun {
+            id: format!("{}-{}", started_at, job_id),
+            job_id: job_id.to_string(),
+            path: job.path.to_string_lossy().into_owned(),
+            datasets: job.datasets.clone(),
+            blocks: job.options.blocks.clone(),
+            indexed_through: job.progress.as_ref().and_then(|p| p.indexed_through),
+            chain_id: job.summary.as_ref().and_then(|s| s.chain_id),
+            options: job.options.clone(),
+            status: job.status,
+            files: job
+                .summary
+                .as_ref()
+                .map(|s| s.files.clone())
+                .unwrap_or_default(),
+            summary: job.summary.clone(),
+            error: job.error.clone(),
+            started_at,
+            duration_secs: elapsed.as_secs_f64(),
+        };
+        drop(jobs);
+
+        let mut state = self.state.lock().await;
+        for file in &run.files {
+            if !state.indexed_files.contains(file) {
+                state.indexed_files.push(file.clone());
             }
         }
+        drop(state);
+        let files = run.files.clone();
+        self.manifest.lock().await.record(run);
+        let _ = self.files_sender.send(files);
+    }
+
+    pub async fn list_runs(&self, filter: &RunFilter) -> Vec<IndexerRun> {
+        self.manifest.lock().await.list(filter)
+    }
+
+    // Every file written by a recorded run
+    pub async fn run_files(&self) -> Vec<String> {
+        self.manifest
+            .lock()
+            .await
+            .runs()
+            .iter()
+            .flat_map(|run| run.files.iter().cloned())
+            .collect()
+    }
+
+    pub async fn delete_run(&self, run_id: &str, delete_files: bool) -> Result<(), String> {
+        let run = self.manifest.lock().await.delete(run_id, delete_files)?;
+        if delete_files {
+            self.state
+                .lock()
+                .await
+                .indexed_files
+                .retain(|file| !run.files.contains(file));
+        }
+        self.log(format!("Deleted indexer run {}", run_id)).await;
+        Ok(())
+    }
+
+    async fn publish_progress(&self, progress: IndexerProgress) {
+        publish_progress(&self.state, &self.jobs, &self.progress_sender, progress).await;
+    }
+
+    // Record a message in the state and forward it to log subscribers
+    async fn log(&self, message: String) {
+        self.state.lock().await.log_messages.push(message.clone());
+        let _ = self.log_sender.send(message);
     }
 
     fn create_args(
         &self,
         path: &PathBuf,
-        dataset: &str,
+        datasets: &[String],
         options: &IndexerOptions,
     ) -> Result<Args, String> {
         let mut args = Args::default();
         args.output_dir = path.to_str().ok_or("Invalid path")?.to_string();
-        args.datatype = vec![dataset.to_string()];
-        args.rpc = options.rpc.clone();
+        args.datatype = datasets.to_vec();
+        // Cryo needs a URL, so resolve registered endpoint names and carry
+        // over the settings cryo has an option for. It has no request
+        // timeout, so an endpoint's `timeout` doesn't apply to indexing.
+        let endpoint = match options.rpc.as_deref() {
+            Some(rpc) => Some(cryo_endpoint(rpc).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        args.rpc = endpoint.as_ref().map(|endpoint| endpoint.url.clone());
         args.blocks = options.blocks.clone();
         args.align = options.align;
         args.reorg_buffer = options.reorg_buffer;
@@ -213,8 +1020,14 @@ impl IndexerTool {
         args.exclude_columns = options.exclude_columns.clone();
         args.sort = options.sort.clone();
         args.exclude_failed = options.exclude_failed;
-        args.requests_per_second = options.requests_per_second;
-        args.max_retries = options.max_retries;
+        args.requests_per_second = options.requests_per_second.or_else(|| {
+            let rate_limit = endpoint.as_ref()?.rate_limit?;
+            Some(u32::try_from(rate_limit).unwrap_or(u32::MAX))
+        });
+        args.max_retries = match endpoint.as_ref().and_then(|e| e.max_retries) {
+            Some(max_retries) if options.max_retries == CRYO_MAX_RETRIES => max_retries,
+            _ => options.max_retries,
+        };
         args.chunk_size = options.chunk_size;
         args.overwrite = options.overwrite;
         args.csv = options.csv;
@@ -223,7 +1036,12 @@ impl IndexerTool {
 
         // Set default values
         args.compression = vec!["lz4".to_string()];
-        args.initial_backoff = options.initial_backoff;
+        args.initial_backoff = match endpoint.as_ref().and_then(|e| e.initial_backoff) {
+            Some(initial_backoff) if options.initial_backoff == CRYO_INITIAL_BACKOFF => {
+                initial_backoff
+            }
+            _ => options.initial_backoff,
+        };
         args.no_stats = options.no_stats;
         args.inner_request_size = options.inner_request_size;
 
@@ -234,7 +1052,6 @@ impl IndexerTool {
         args.u256_types = options.u256_types.clone();
         args.hex = options.hex;
         args.network_name = options.network_name.clone();
-        args.initial_backoff = options.initial_backoff;
         args.max_concurrent_requests = options.max_concurrent_requests;
         args.max_concurrent_chunks = options.max_concurrent_chunks;
         args.chunk_order = options.chunk_order.clone();
@@ -271,31 +1088,283 @@ impl IndexerTool {
     }
 
     pub async fn set_selected_dataset(&self, dataset: String) -> Result<(), String> {
-        let mut state = self.state.lock().await;
-        if state.available_datasets.contains(&dataset) {
-            state.selected_dataset = Some(dataset);
-            Ok(())
-        } else {
-            Err("Invalid dataset selected".to_string())
-        }
+        let schema = self
+            .dataset_schema(&dataset)
+            .map_err(|_| "Invalid dataset selected".to_string())?;
+        self.state.lock().await.selected_dataset = Some(schema.name().to_string());
+        Ok(())
     }
 
     pub async fn get_available_datasets(&self) -> Vec<String> {
         self.state.lock().await.available_datasets.clone()
     }
 
+    pub fn validate_options(
+        &self,
+        datasets: &[String],
+        options: &IndexerOptions,
+    ) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+        if datasets.is_empty() {
+            errors.push(FieldError::new(
+                "datasets",
+                "At least one dataset is required",
+                None,
+            ));
+        }
+        if let Some(Err(e)) = options.rpc.as_deref().map(cryo_endpoint) {
+            errors.push(e);
+        }
+        let mut schemas = Vec::new();
+        for dataset in datasets {
+            match self.dataset_schema(dataset) {
+                Ok(schema) => schemas.push(schema),
+                Err(e) => errors.push(FieldError::new("datasets", e, Some(dataset.as_str()))),
+            }
+        }
+        errors.extend(validate_options(&schemas, options));
+        errors
+    }
+
+    pub fn dataset_catalog(&self) -> Vec<DatasetSchema> {
+        self.catalog.clone()
+    }
+
+    // Look up a dataset by name or alias
+    pub fn dataset_schema(&self, name: &str) -> Result<&DatasetSchema, String> {
+        self.catalog
+            .iter()
+            .find(|schema| schema.matches(name))
+            .ok_or_else(|| format!("Unknown dataset: {}", name))
+    }
+
     pub fn subscribe_to_logs(&self) -> broadcast::Receiver<String> {
         self.log_sender.subscribe()
     }
+
+    pub fn subscribe_to_progress(&self) -> broadcast::Receiver<IndexerProgress> {
+        self.progress_sender.subscribe()
+    }
+
+    // Files written by runs as they're added to the manifest, and by
+    // followers once their backfill is done
+    pub fn subscribe_to_files(&self) -> broadcast::Receiver<Vec<String>> {
+        self.files_sender.subscribe()
+    }
 }
 
-#[derive(Debug, Serialize, Clone)]
+impl IndexerState {
+    fn set_progress(&mut self, progress: IndexerProgress) {
+        self.indexing_progress = if progress.status == JobStatus::Completed {
+            100.0
+        } else if progress.total_chunks > 0 {
+            progress.completed_chunks as f32 * 100.0 / progress.total_chunks as f32
+        } else {
+            0.0
+        };
+        self.progress = Some(progress);
+    }
+}
+
+// Removed jobs that haven't stopped yet are treated as gone
+fn find_job<'a>(jobs: &'a [IndexerJob], job_id: &str) -> Result<&'a IndexerJob, String> {
+    jobs.iter()
+        .find(|job| job.id == job_id && !job.removed)
+        .ok_or_else(|| format!("Unknown indexing job: {}", job_id))
+}
+
+fn find_job_mut<'a>(
+    jobs: &'a mut [IndexerJob],
+    job_id: &str,
+) -> Result<&'a mut IndexerJob, String> {
+    jobs.iter_mut()
+        .find(|job| job.id == job_id && !job.removed)
+        .ok_or_else(|| format!("Unknown indexing job: {}", job_id))
+}
+
+// Drop a job that was removed while running, once it has stopped
+fn take_removed(jobs: &mut Vec<IndexerJob>, job_id: &str) -> bool {
+    let count = jobs.len();
+    jobs.retain(|job| job.id != job_id || !job.removed);
+    jobs.len() < count
+}
+
+// Record progress on the job and the overall state, then notify subscribers
+async fn publish_progress(
+    state: &Mutex<IndexerState>,
+    jobs: &Mutex<Vec<IndexerJob>>,
+    sender: &broadcast::Sender<IndexerProgress>,
+    progress: IndexerProgress,
+) {
+    if let Ok(job) = find_job_mut(&mut jobs.lock().await, &progress.job_id) {
+        job.progress = Some(progress.clone());
+    }
+    state.lock().await.set_progress(progress.clone());
+    let _ = sender.send(progress);
+}
+
+// Resolve the endpoint cryo should index from. Cryo only takes a URL, so
+// endpoints that need headers, e.g. for auth, can't be used.
+fn cryo_endpoint(rpc: &str) -> Result<RpcEndpoint, FieldError> {
+    let endpoint = resolve_endpoint(rpc);
+    if !endpoint.headers.is_empty() {
+        return Err(FieldError::new(
+            "rpc",
+            "Indexing can't send the HTTP headers this endpoint needs",
+            Some(rpc),
+        ));
+    }
+    Ok(endpoint)
+}
+
+// Latest block that's at least `reorg_buffer` blocks deep
+async fn safe_head(options: &IndexerOptions) -> Result<u64, String> {
+    // Same fallback cryo uses when no RPC is given
+    let rpc = options
+        .rpc
+        .clone()
+        .or_else(|| std::env::var("ETH_RPC_URL").ok())
+        .ok_or("An RPC URL is required to follow the chain head")?;
+    let head = CastWrapper::block_number(&rpc)
+        .await
+        .map_err(|e| e.to_string())?;
+    let head = head
+        .parse::<u64>()
+        .map_err(|e| format!("Invalid block number {}: {}", head, e))?;
+    Ok(head.saturating_sub(options.reorg_buffer))
+}
+
+// Cryo writes each chunk to a temporary file before renaming it into place,
+// so a stopped run can leave those behind. The output dir may hold unrelated
+// files, so only the subdirs cryo creates are searched, one level per entry
+// in `subdirs`.
+fn remove_tmp_files(dir: &Path, subdirs: &[String]) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        match subdirs.split_first() {
+            Some((subdir, rest)) if path.is_dir() => {
+                // `network` and `datatype` dirs are named after the run
+                let named = matches!(subdir.as_str(), "network" | "datatype");
+                if named || name == subdir.as_str() {
+                    remove_tmp_files(&path, rest);
+                }
+            }
+            None if path.is_file() && is_cryo_tmp_file(&name) => {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    error!(target: "cryo", "Failed to remove partial chunk {:?}: {}", path, e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Cryo names chunks `<network>__<datatype>__<blocks>.<format>` and appends
+// `_tmp` while writing them
+fn is_cryo_tmp_file(name: &str) -> bool {
+    let Some(stem) = [".parquet_tmp", ".csv_tmp", ".json_tmp"]
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+    else {
+        return false;
+    };
+    let parts: Vec<&str> = stem.split("__").collect();
+    parts.len() >= 3 && parts.iter().all(|part| !part.is_empty())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct CompactFreezeSummary {
     completed_chunks: usize,
     skipped_chunks: usize,
     errored_chunks: usize,
     total_chunks: usize,
     rows_written: u64,
+    #[serde(default)]
+    chain_id: Option<u64>,
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    errored: Vec<ErroredChunk>,
+    // Highest block covered by a written or already existing chunk
+    #[serde(default)]
+    last_block: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ErroredChunk {
+    // Block range of the chunk, if it was split by block
+    start_block: Option<u64>,
+    end_block: Option<u64>,
+    error: String,
+}
+
+// Block chunks of the partitions, if they were split by block
+fn block_chunks<'a>(
+    partitions: impl IntoIterator<Item = &'a Partition>,
+) -> impl Iterator<Item = &'a BlockChunk> {
+    partitions
+        .into_iter()
+        .flat_map(|p| p.block_numbers.iter().chain(p.block_ranges.iter()))
+        .flatten()
+}
+
+impl ErroredChunk {
+    fn new(partition: Option<&Partition>, error: String) -> Self {
+        let chunks: Vec<_> = block_chunks(partition).collect();
+        ErroredChunk {
+            start_block: chunks.iter().filter_map(|c| c.min_value()).min(),
+            end_block: chunks.iter().filter_map(|c| c.max_value()).max(),
+            error,
+        }
+    }
+
+    // Cryo block spec covering the chunk
+    fn blocks(&self) -> Option<String> {
+        let (start, end) = (self.start_block?, self.end_block?);
+        Some(format!("{}:+{}", start, end - start + 1))
+    }
+}
+
+impl CompactFreezeSummary {
+    fn merge(&mut self, other: &CompactFreezeSummary) {
+        self.completed_chunks += other.completed_chunks;
+        self.skipped_chunks += other.skipped_chunks;
+        self.errored_chunks += other.errored_chunks;
+        self.total_chunks += other.total_chunks;
+        self.rows_written += other.rows_written;
+        self.files.extend(other.files.iter().cloned());
+        self.errored.extend(other.errored.iter().cloned());
+        self.last_block = self.last_block.max(other.last_block);
+    }
+
+    fn to_progress(&self, job_id: &str, datasets: &[String], elapsed: Duration) -> IndexerProgress {
+        let elapsed_secs = elapsed.as_secs_f64();
+        let processed = self.completed_chunks + self.errored_chunks;
+        IndexerProgress {
+            job_id: job_id.to_string(),
+            status: JobStatus::Completed,
+            datasets: datasets.to_vec(),
+            completed_chunks: self.completed_chunks,
+            skipped_chunks: Some(self.skipped_chunks),
+            errored_chunks: Some(self.errored_chunks),
+            total_chunks: self.total_chunks,
+            rows_written: Some(self.rows_written),
+            elapsed_secs,
+            eta_secs: Some(0.0),
+            chunks_per_second: if elapsed_secs > 0.0 {
+                processed as f64 / elapsed_secs
+            } else {
+                0.0
+            },
+            indexed_through: None,
+        }
+    }
 }
 
 impl From<FreezeSummary> for CompactFreezeSummary {
@@ -307,6 +1376,49 @@ impl From<FreezeSummary> for CompactFreezeSummary {
             errored_chunks: summary.errored.len(),
             total_chunks,
             rows_written: summary.n_rows,
+            chain_id: None,
+            files: summary
+                .paths_by_type
+                .values()
+                .flatten()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+            errored: summary
+                .errored
+                .iter()
+                .map(|(partition, error)| ErroredChunk::new(partition.as_ref(), error.to_string()))
+                .collect(),
+            last_block: block_chunks(summary.completed.iter().chain(summary.skipped.iter()))
+                .filter_map(|chunk| chunk.max_value())
+                .max(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_cryo_tmp_files() {
+        assert!(is_cryo_tmp_file(
+            "ethereum__blocks__00017000000_to_00017000999.parquet_tmp"
+        ));
+        assert!(is_cryo_tmp_file(
+            "ethereum__logs__00017000000_to_00017000999__mylabel.csv_tmp"
+        ));
+        assert!(is_cryo_tmp_file("base__transactions__00000000.json_tmp"));
+    }
+
+    #[test]
+    fn ignores_other_files() {
+        assert!(!is_cryo_tmp_file(
+            "ethereum__blocks__00017000000_to_00017000999.parquet"
+        ));
+        assert!(!is_cryo_tmp_file("notes.txt_tmp"));
+        assert!(!is_cryo_tmp_file("backup.parquet_tmp"));
+        assert!(!is_cryo_tmp_file("a__b.parquet_tmp"));
+        assert!(!is_cryo_tmp_file("__blocks__1.parquet_tmp"));
+        assert!(!is_cryo_tmp_file("ethereum__blocks__1.xlsx_tmp"));
+    }
+}
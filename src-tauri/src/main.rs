@@ -1,190 +1,327 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use alloy::{
-    hex,
-    node_bindings::anvil::{Anvil, AnvilInstance},
-};
 use env_logger::Builder;
-use log::{info, LevelFilter};
+use log::{error, info, LevelFilter};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::api::path::home_dir;
-use tauri::Manager;
+use std::time::Duration;
+use tauri::{Manager, RunEvent};
 use tokio::sync::Mutex;
 
-struct DevnetState {
-    instance: Option<AnvilInstance>,
-    logs: Arc<Mutex<Vec<String>>>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-struct DevnetInfo {
-    rpc_url: String,
-    chain_id: u64,
-    client_version: String,
-    addresses: Vec<String>,
-    private_keys: Vec<String>,
-}
-
-fn find_anvil() -> Option<PathBuf> {
-    let home = home_dir()?;
-    let possible_paths = vec![
-        home.join(".foundry/bin/anvil"),
-        // PathBuf::from("/usr/local/bin/anvil"),
-        // PathBuf::from("/usr/bin/anvil"),
-    ];
+mod anvil;
+mod devnet;
 
-    for path in possible_paths {
-        if path.exists() {
-            return Some(path);
-        }
-    }
-
-    None
-}
+use anvil::{AnvilBinary, AnvilError};
+use devnet::{
+    DevnetConfig, DevnetInfo, DevnetProfile, DevnetState, ForkOptions, SavedDevnetState,
+    DEFAULT_DEVNET_ID,
+};
 
 #[tauri::command]
 async fn start_devnet(
+    id: Option<String>,
+    config: Option<DevnetConfig>,
     state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+    window: tauri::Window,
 ) -> Result<DevnetInfo, String> {
-    let mut state = state.lock().await;
-    if state.instance.is_some() {
-        return Err("Devnet is already running".to_string());
-    }
-
-    // Find Anvil
-    let anvil_path = find_anvil().ok_or_else(|| {
-        "Anvil not found. Please ensure Foundry is installed and Anvil is in your PATH.".to_string()
-    })?;
-
-    // Spawn Anvil
-    let instance = match Anvil::at(anvil_path).try_spawn() {
-        Ok(inst) => inst,
-        Err(e) => {
-            let msg = format!("Failed to spawn Anvil instance: {}", e);
-            return Err(msg);
-        }
-    };
-
-    let devnet_info = DevnetInfo {
-        rpc_url: instance.endpoint(),
-        chain_id: instance.chain_id(),
-        client_version: "Anvil".to_string(),
-        addresses: instance
-            .addresses()
-            .iter()
-            .map(|addr| format!("{:?}", addr))
-            .collect(),
-        private_keys: instance
-            .keys()
-            .iter()
-            .map(|key| format!("0x{}", hex::encode(key.to_bytes())))
-            .collect(),
-    };
-
-    state.instance = Some(instance);
-    Ok(devnet_info)
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    devnet::start_devnet(&state, id, config.unwrap_or_default(), window).await
 }
 
 #[tauri::command]
-async fn stop_devnet(state: tauri::State<'_, Arc<Mutex<DevnetState>>>) -> Result<(), String> {
+async fn stop_devnet(
+    id: Option<String>,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
     let mut state = state.lock().await;
-    if let Some(instance) = state.instance.take() {
-        // AnvilInstance doesn't have a `kill` method, so we'll just drop it
-        drop(instance);
-    }
-    Ok(())
+    state.stop(&id)
+}
+
+#[tauri::command]
+async fn list_devnets(
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<Vec<DevnetInfo>, String> {
+    Ok(state.lock().await.list())
 }
 
 #[tauri::command]
 async fn get_devnet_status(
+    id: Option<String>,
     state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
 ) -> Result<bool, String> {
-    let devnet_state = state.lock().await;
-    Ok(devnet_state.instance.is_some())
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    Ok(state.lock().await.is_running(&id))
 }
 
 #[tauri::command]
 async fn get_devnet_logs(
+    id: Option<String>,
     state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
 ) -> Result<Vec<String>, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
     let state = state.lock().await;
-    let logs = state.logs.lock().await;
-    Ok(logs.clone())
+    Ok(state.get(&id)?.logs().await)
 }
 
 #[tauri::command]
 async fn get_devnet_wallets(
+    id: Option<String>,
     state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
 ) -> Result<Vec<serde_json::Value>, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
     let state = state.lock().await;
-    if let Some(instance) = &state.instance {
-        let accounts = instance.addresses();
-        let private_keys = instance.keys();
-
-        Ok(accounts
-            .into_iter()
-            .zip(private_keys)
-            .map(|(address, private_key)| {
-                serde_json::json!({
-                    "address": format!("{:?}", address),
-                    "privateKey": format!("0x{}", hex::encode(private_key.to_bytes())),
-                })
-            })
-            .collect())
-    } else {
-        Err("Devnet is not running".to_string())
-    }
+    Ok(state.get(&id)?.wallets())
 }
 
 #[tauri::command]
 async fn fork_network(
+    id: Option<String>,
     url: String,
+    options: Option<ForkOptions>,
+    config: Option<DevnetConfig>,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+    window: tauri::Window,
+) -> Result<DevnetInfo, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    devnet::fork_devnet(
+        &state,
+        id,
+        url,
+        options.unwrap_or_default(),
+        config.unwrap_or_default(),
+        window,
+    )
+    .await
+}
+
+#[tauri::command]
+async fn get_devnet_info(
+    id: Option<String>,
     state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<DevnetInfo, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let state = state.lock().await;
+    Ok(state.get(&id)?.info(&id))
+}
+
+#[tauri::command]
+fn list_devnet_profiles(app_handle: tauri::AppHandle) -> Result<Vec<DevnetProfile>, String> {
+    devnet::list_profiles(&app_handle)
+}
+
+#[tauri::command]
+fn create_devnet_profile(
+    profile: DevnetProfile,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let mut state = state.lock().await;
-    if state.instance.is_some() {
-        return Err("Devnet is already running".to_string());
-    }
+    devnet::create_profile(&app_handle, &profile)
+}
 
-    // Spawn Anvil with fork
-    let instance = Anvil::new().fork(url).spawn();
+#[tauri::command]
+fn update_devnet_profile(
+    name: String,
+    profile: DevnetProfile,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    devnet::update_profile(&app_handle, &name, &profile)
+}
 
-    state.instance = Some(instance);
-    Ok(())
+#[tauri::command]
+fn delete_devnet_profile(name: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    devnet::delete_profile(&app_handle, &name)
 }
 
 #[tauri::command]
-async fn get_devnet_info(
+async fn launch_devnet_profile(
+    name: String,
+    id: Option<String>,
     state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+    app_handle: tauri::AppHandle,
+    window: tauri::Window,
 ) -> Result<DevnetInfo, String> {
-    let state = state.lock().await;
-    if let Some(instance) = &state.instance {
-        Ok(DevnetInfo {
-            rpc_url: instance.endpoint(),
-            chain_id: instance.chain_id(),
-            client_version: "Anvil".to_string(),
-            addresses: instance
-                .addresses()
-                .iter()
-                .map(|addr| format!("{:?}", addr))
-                .collect(),
-            private_keys: instance
-                .keys()
-                .iter()
-                .map(|key| format!("0x{}", hex::encode(key.to_bytes())))
-                .collect(),
-        })
-    } else {
-        Err("Devnet is not running".to_string())
-    }
+    let profile = devnet::get_profile(&app_handle, &name)?;
+    let id = id.unwrap_or_else(|| profile.name.clone());
+    devnet::launch_profile(&state, id, profile, window).await
 }
 
+#[tauri::command]
+async fn find_anvil(
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<AnvilBinary, AnvilError> {
+    state.lock().await.find_anvil()
+}
+
+#[tauri::command]
+async fn set_anvil_paths(
+    paths: Vec<String>,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let mut state = state.lock().await;
+    state.set_anvil_paths(paths.into_iter().map(PathBuf::from).collect())
+}
+
+#[tauri::command]
+async fn dump_devnet_state(
+    id: Option<String>,
+    name: String,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+    app_handle: tauri::AppHandle,
+) -> Result<SavedDevnetState, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.dump_state(&app_handle, &name).await
+}
+
+#[tauri::command]
+async fn load_devnet_state(
+    id: Option<String>,
+    name: String,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.load_state(&app_handle, &name).await
+}
+
+#[tauri::command]
+fn list_devnet_states(app_handle: tauri::AppHandle) -> Result<Vec<SavedDevnetState>, String> {
+    devnet::list_saved_states(&app_handle)
+}
+
+#[tauri::command]
+fn delete_devnet_state(name: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    devnet::delete_saved_state(&app_handle, &name)
+}
+
+#[tauri::command]
+async fn snapshot_devnet(
+    id: Option<String>,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<String, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.snapshot().await
+}
+
+#[tauri::command]
+async fn revert_devnet(
+    id: Option<String>,
+    snapshot_id: String,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<bool, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.revert(&snapshot_id).await
+}
+
+#[tauri::command]
+async fn devnet_impersonate_account(
+    id: Option<String>,
+    address: String,
+    enabled: bool,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.impersonate_account(&address, enabled).await
+}
+
+#[tauri::command]
+async fn devnet_set_balance(
+    id: Option<String>,
+    address: String,
+    wei: String,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.set_balance(&address, &wei).await
+}
+
+#[tauri::command]
+async fn devnet_set_code(
+    id: Option<String>,
+    address: String,
+    code: String,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.set_code(&address, &code).await
+}
+
+#[tauri::command]
+async fn devnet_set_storage_at(
+    id: Option<String>,
+    address: String,
+    slot: String,
+    value: String,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.set_storage_at(&address, &slot, &value).await
+}
+
+#[tauri::command]
+async fn devnet_set_nonce(
+    id: Option<String>,
+    address: String,
+    nonce: u64,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.set_nonce(&address, nonce).await
+}
+
+#[tauri::command]
+async fn devnet_increase_time(
+    id: Option<String>,
+    seconds: u64,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<i64, String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.increase_time(seconds).await
+}
+
+#[tauri::command]
+async fn devnet_set_next_block_timestamp(
+    id: Option<String>,
+    timestamp: u64,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.set_next_block_timestamp(timestamp).await
+}
+
+#[tauri::command]
+async fn devnet_set_automine(
+    id: Option<String>,
+    enabled: bool,
+    state: tauri::State<'_, Arc<Mutex<DevnetState>>>,
+) -> Result<(), String> {
+    let id = id.unwrap_or_else(|| DEFAULT_DEVNET_ID.to_string());
+    let devnet = state.lock().await.get(&id)?.client();
+    devnet.set_automine(enabled).await
+}
+
+mod datasets;
 mod indexer;
+mod manifest;
+mod persist;
+mod validation;
 
-use indexer::{CompactFreezeSummary, IndexerOptions, IndexerState, IndexerTool};
+use datasets::DatasetSchema;
+use indexer::{CompactFreezeSummary, IndexerJobInfo, IndexerOptions, IndexerState, IndexerTool};
+use manifest::{IndexerRun, RunFilter};
+use validation::FieldError;
 
 #[tauri::command]
 async fn start_indexing(
@@ -196,6 +333,81 @@ async fn start_indexing(
     indexer.start_indexing(path.into(), dataset, options).await
 }
 
+#[tauri::command]
+async fn enqueue_indexing(
+    path: String,
+    datasets: Vec<String>,
+    options: IndexerOptions,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<String, String> {
+    indexer
+        .enqueue_indexing(path.into(), datasets, options)
+        .await
+}
+
+#[tauri::command]
+async fn list_indexing_jobs(
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<Vec<IndexerJobInfo>, String> {
+    Ok(indexer.list_jobs().await)
+}
+
+#[tauri::command]
+async fn get_indexing_job(
+    job_id: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<IndexerJobInfo, String> {
+    indexer.get_job(&job_id).await
+}
+
+#[tauri::command]
+async fn retry_failed_chunks(
+    job_id: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<String, String> {
+    indexer.retry_failed_chunks(&job_id).await
+}
+
+#[tauri::command]
+async fn remove_indexing_job(
+    job_id: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<(), String> {
+    indexer.remove_job(&job_id).await
+}
+
+#[tauri::command]
+async fn set_max_concurrent_indexing_jobs(
+    limit: usize,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<(), String> {
+    indexer.set_max_concurrent_jobs(limit).await
+}
+
+#[tauri::command]
+async fn pause_indexing(
+    job_id: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<(), String> {
+    indexer.pause_indexing(&job_id).await
+}
+
+#[tauri::command]
+async fn resume_indexing(
+    job_id: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<(), String> {
+    indexer.resume_indexing(&job_id).await
+}
+
+#[tauri::command]
+async fn cancel_indexing(
+    job_id: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<(), String> {
+    indexer.cancel_indexing(&job_id).await
+}
+
 #[tauri::command]
 async fn get_indexer_state(
     indexer: tauri::State<'_, Arc<IndexerTool>>,
@@ -218,6 +430,70 @@ async fn get_available_datasets(
     Ok(indexer.get_available_datasets().await)
 }
 
+#[tauri::command]
+async fn validate_indexer_options(
+    datasets: Vec<String>,
+    options: IndexerOptions,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<Vec<FieldError>, String> {
+    Ok(indexer.validate_options(&datasets, &options))
+}
+
+#[tauri::command]
+async fn list_indexer_runs(
+    filter: Option<RunFilter>,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<Vec<IndexerRun>, String> {
+    Ok(indexer.list_runs(&filter.unwrap_or_default()).await)
+}
+
+#[tauri::command]
+async fn delete_indexer_run(
+    run_id: String,
+    delete_files: bool,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<(), String> {
+    indexer.delete_run(&run_id, delete_files).await?;
+    // Views may have read the run's files; rebuild them from what's left
+    let files = indexer.run_files().await;
+    analyzer
+        .run_blocking(move |analyzer| {
+            analyzer.rebuild_views(&files);
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+async fn get_dataset_catalog(
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<Vec<DatasetSchema>, String> {
+    Ok(indexer.dataset_catalog())
+}
+
+#[tauri::command]
+async fn get_dataset_schema(
+    dataset: String,
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+) -> Result<DatasetSchema, String> {
+    indexer.dataset_schema(&dataset).cloned()
+}
+
+#[tauri::command]
+async fn subscribe_to_indexer_progress(
+    indexer: tauri::State<'_, Arc<IndexerTool>>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    let mut rx = indexer.subscribe_to_progress();
+    tokio::spawn(async move {
+        while let Ok(progress) = rx.recv().await {
+            let _ = window.emit("indexer-progress", progress);
+        }
+    });
+    Ok(())
+}
+
 #[tauri::command]
 async fn subscribe_to_indexer_logs(
     indexer: tauri::State<'_, Arc<IndexerTool>>,
@@ -233,12 +509,165 @@ async fn subscribe_to_indexer_logs(
 }
 
 mod chain_analyzer;
+mod query_result;
+
+use chain_analyzer::{ChainAnalyzer, QueryCursor, TableInfo, WorkspaceInfo};
+use query_result::QueryResult;
+
+// Queries run on a blocking thread under a caller-chosen id, which
+// `interrupt_query` takes to stop them early
+#[tauri::command]
+async fn execute_query_command(
+    query_id: String,
+    query: String,
+    timeout_ms: Option<u64>,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<QueryResult, String> {
+    let id = query_id.clone();
+    analyzer
+        .run_with_timeout(
+            query_id,
+            timeout_ms.map(Duration::from_millis),
+            move |analyzer| analyzer.execute_query(&id, &query),
+        )
+        .await
+}
+
+#[tauri::command]
+async fn open_query_cursor(
+    query_id: String,
+    query: String,
+    row_cap: Option<u64>,
+    timeout_ms: Option<u64>,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<QueryCursor, String> {
+    let id = query_id.clone();
+    analyzer
+        .run_with_timeout(
+            query_id,
+            timeout_ms.map(Duration::from_millis),
+            move |analyzer| analyzer.open_cursor(&id, &query, row_cap),
+        )
+        .await
+}
+
+#[tauri::command]
+fn interrupt_query(
+    query_id: String,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<(), String> {
+    analyzer.interrupt_query(&query_id)
+}
 
-use chain_analyzer::{execute_query, QueryResult};
+#[tauri::command]
+async fn fetch_query_page(
+    cursor_id: u64,
+    offset: u64,
+    limit: u64,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<QueryResult, String> {
+    analyzer
+        .run_blocking(move |analyzer| analyzer.fetch_page(cursor_id, offset, limit))
+        .await
+}
+
+// Emits `query-batch` events until the cursor has been read to the end
+#[tauri::command]
+async fn stream_query_cursor(
+    cursor_id: u64,
+    batch_size: u64,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+    window: tauri::Window,
+) -> Result<(), String> {
+    analyzer
+        .run_blocking(move |analyzer| {
+            analyzer.stream_cursor(cursor_id, batch_size, |batch| {
+                let _ = window.emit("query-batch", batch);
+            })
+        })
+        .await
+}
+
+#[tauri::command]
+async fn close_query_cursor(
+    cursor_id: u64,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<(), String> {
+    analyzer
+        .run_blocking(move |analyzer| analyzer.close_cursor(cursor_id))
+        .await
+}
+
+// Counting rows in every view can take a while on large csv/json files
+#[tauri::command]
+async fn list_analyzer_tables(
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<Vec<TableInfo>, String> {
+    analyzer
+        .run_blocking(|analyzer| analyzer.list_tables().map_err(|e| e.to_string()))
+        .await
+}
 
 #[tauri::command]
-fn execute_query_command(query: &str) -> Result<QueryResult, String> {
-    execute_query(query).map_err(|e| e.to_string())
+async fn get_analyzer_workspace(
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<WorkspaceInfo, String> {
+    analyzer
+        .run_blocking(|analyzer| Ok(analyzer.workspace()))
+        .await
+}
+
+#[tauri::command]
+async fn open_analyzer_workspace(
+    path: String,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<WorkspaceInfo, String> {
+    analyzer
+        .run_blocking(move |analyzer| analyzer.open_workspace(path.into()))
+        .await
+}
+
+#[tauri::command]
+async fn create_analyzer_workspace(
+    path: String,
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<WorkspaceInfo, String> {
+    analyzer
+        .run_blocking(move |analyzer| analyzer.create_workspace(path.into()))
+        .await
+}
+
+#[tauri::command]
+async fn close_analyzer_workspace(
+    analyzer: tauri::State<'_, Arc<ChainAnalyzer>>,
+) -> Result<WorkspaceInfo, String> {
+    analyzer
+        .run_blocking(|analyzer| analyzer.close_workspace())
+        .await
+}
+
+mod endpoints;
+
+use endpoints::{EndpointStatus, RpcEndpoint};
+
+#[tauri::command]
+fn list_rpc_endpoints() -> Vec<RpcEndpoint> {
+    endpoints::list_endpoints()
+}
+
+#[tauri::command]
+fn add_rpc_endpoint(endpoint: RpcEndpoint) -> Result<(), String> {
+    endpoints::add_endpoint(endpoint)
+}
+
+#[tauri::command]
+fn remove_rpc_endpoint(name: String) -> Result<(), String> {
+    endpoints::remove_endpoint(&name)
+}
+
+#[tauri::command]
+async fn test_rpc_endpoint(rpc: String) -> Result<EndpointStatus, String> {
+    endpoints::test_endpoint(&rpc).await
 }
 
 mod cast_wrapper; // Add this line to import the cast_wrapper module
@@ -498,12 +927,24 @@ fn main() {
 
     info!("Starting application");
 
-    let devnet_state = Arc::new(Mutex::new(DevnetState {
-        instance: None,
-        logs: Arc::new(Mutex::new(Vec::new())),
-    }));
+    let devnet_state = Arc::new(Mutex::new(DevnetState::default()));
+    devnet::watch_devnets(devnet_state.clone());
 
     let indexer = Arc::new(IndexerTool::new());
+    indexer.start_scheduler();
+    let analyzer = Arc::new(ChainAnalyzer::new().expect("failed to open DuckDB"));
+
+    // Make indexer output queryable by dataset name
+    let mut files = indexer.subscribe_to_files();
+    let views = analyzer.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Ok(files) = files.recv().await {
+            let views = views.clone();
+            // Waits for the workspace, which a long query may be holding
+            let _ =
+                tauri::async_runtime::spawn_blocking(move || views.register_views(&files)).await;
+        }
+    });
 
     tauri::Builder::default()
         .setup(|app| {
@@ -512,26 +953,110 @@ fn main() {
                 let window = app.get_window("main").unwrap();
                 window.open_devtools();
             }
+
+            if let Some(dir) = app.path_resolver().app_data_dir() {
+                let pid_file = dir.join("devnet-pids.json");
+                devnet::cleanup_stale_devnets(&pid_file);
+                if let Err(e) = std::fs::create_dir_all(&dir) {
+                    error!("Failed to create app data directory: {}", e);
+                }
+                let state = app.state::<Arc<Mutex<DevnetState>>>();
+                tauri::async_runtime::block_on(state.lock()).set_pid_file(pid_file);
+                let indexer = app.state::<Arc<IndexerTool>>();
+                tauri::async_runtime::block_on(
+                    indexer.load_manifest(dir.join("indexer-runs.json")),
+                );
+                let analyzer = app.state::<Arc<ChainAnalyzer>>();
+                let files = tauri::async_runtime::block_on(indexer.run_files());
+                analyzer.rebuild_views(&files);
+            }
+            if let Some(dir) = app.path_resolver().app_config_dir() {
+                let state = app.state::<Arc<Mutex<DevnetState>>>();
+                tauri::async_runtime::block_on(state.lock())
+                    .load_anvil_paths(dir.join("anvil-paths.json"));
+                endpoints::load_endpoints(dir.join("rpc-endpoints.json"));
+            }
             Ok(())
         })
         .manage(devnet_state)
         .manage(indexer)
+        .manage(analyzer)
         .invoke_handler(tauri::generate_handler![
             start_devnet,
             stop_devnet,
+            list_devnets,
             get_devnet_status,
             get_devnet_logs,
             get_devnet_wallets,
             fork_network,
             get_devnet_info,
+            list_devnet_profiles,
+            create_devnet_profile,
+            update_devnet_profile,
+            delete_devnet_profile,
+            launch_devnet_profile,
+            find_anvil,
+            set_anvil_paths,
+            dump_devnet_state,
+            load_devnet_state,
+            list_devnet_states,
+            delete_devnet_state,
+            snapshot_devnet,
+            revert_devnet,
+            devnet_impersonate_account,
+            devnet_set_balance,
+            devnet_set_code,
+            devnet_set_storage_at,
+            devnet_set_nonce,
+            devnet_increase_time,
+            devnet_set_next_block_timestamp,
+            devnet_set_automine,
             start_indexing,
+            enqueue_indexing,
+            list_indexing_jobs,
+            get_indexing_job,
+            remove_indexing_job,
+            retry_failed_chunks,
+            set_max_concurrent_indexing_jobs,
+            pause_indexing,
+            resume_indexing,
+            cancel_indexing,
             get_indexer_state,
             set_selected_dataset,
             get_available_datasets,
+            get_dataset_catalog,
+            get_dataset_schema,
+            validate_indexer_options,
+            list_indexer_runs,
+            delete_indexer_run,
             subscribe_to_indexer_logs,
+            subscribe_to_indexer_progress,
             execute_query_command,
+            open_query_cursor,
+            fetch_query_page,
+            stream_query_cursor,
+            close_query_cursor,
+            interrupt_query,
+            list_analyzer_tables,
+            get_analyzer_workspace,
+            open_analyzer_workspace,
+            create_analyzer_workspace,
+            close_analyzer_workspace,
+            list_rpc_endpoints,
+            add_rpc_endpoint,
+            remove_rpc_endpoint,
+            test_rpc_endpoint,
             run_cast_command,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let RunEvent::Exit = event {
+                info!("Shutting down devnets");
+                let state = app_handle.state::<Arc<Mutex<DevnetState>>>();
+                tauri::async_runtime::block_on(async {
+                    state.lock().await.shutdown().await;
+                });
+            }
+        });
 }
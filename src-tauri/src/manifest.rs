@@ -0,0 +1,138 @@
+use crate::indexer::{CompactFreezeSummary, IndexerOptions, JobStatus};
+use crate::persist::{set_aside, write_atomic};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// A finished indexer run as recorded in the manifest
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexerRun {
+    pub id: String,
+    pub job_id: String,
+    pub path: String,
+    pub datasets: Vec<String>,
+    pub blocks: Option<Vec<String>>,
+    // Last block written by a run that followed the chain head
+    pub indexed_through: Option<u64>,
+    pub chain_id: Option<u64>,
+    pub options: IndexerOptions,
+    pub status: JobStatus,
+    pub files: Vec<String>,
+    pub summary: Option<CompactFreezeSummary>,
+    pub error: Option<String>,
+    // Unix timestamp in seconds
+    pub started_at: u64,
+    pub duration_secs: f64,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RunFilter {
+    dataset: Option<String>,
+    status: Option<JobStatus>,
+    chain_id: Option<u64>,
+    path: Option<String>,
+}
+
+impl RunFilter {
+    fn matches(&self, run: &IndexerRun) -> bool {
+        self.dataset
+            .as_ref()
+            .is_none_or(|dataset| run.datasets.contains(dataset))
+            && self.status.is_none_or(|status| run.status == status)
+            && self.chain_id.is_none_or(|id| run.chain_id == Some(id))
+            && self.path.as_ref().is_none_or(|path| &run.path == path)
+    }
+}
+
+// Runs recorded in `indexer-runs.json` in the app data dir, so we know what
+// data is already on disk across restarts
+#[derive(Default)]
+pub struct RunManifest {
+    file: Option<PathBuf>,
+    runs: Vec<IndexerRun>,
+}
+
+impl RunManifest {
+    pub fn load(file: PathBuf) -> Self {
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(_) => {
+                return RunManifest {
+                    file: Some(file),
+                    runs: Vec::new(),
+                }
+            }
+        };
+        match serde_json::from_str(&content) {
+            Ok(runs) => RunManifest {
+                file: Some(file),
+                runs,
+            },
+            Err(e) => {
+                warn!("Unreadable run manifest {:?}: {}", file, e);
+                // Without a backup, keep runs in memory only rather than
+                // overwrite the old history
+                let saved = set_aside(&file);
+                RunManifest {
+                    file: saved.then_some(file),
+                    runs: Vec::new(),
+                }
+            }
+        }
+    }
+
+    pub fn runs(&self) -> &[IndexerRun] {
+        &self.runs
+    }
+
+    pub fn list(&self, filter: &RunFilter) -> Vec<IndexerRun> {
+        self.runs
+            .iter()
+            .filter(|run| filter.matches(run))
+            .cloned()
+            .collect()
+    }
+
+    pub fn record(&mut self, run: IndexerRun) {
+        self.runs.push(run);
+        self.save();
+    }
+
+    // Forget a run and, optionally, remove the files it wrote
+    pub fn delete(&mut self, id: &str, delete_files: bool) -> Result<IndexerRun, String> {
+        let index = self
+            .runs
+            .iter()
+            .position(|run| run.id == id)
+            .ok_or_else(|| format!("Unknown indexer run: {}", id))?;
+
+        if delete_files {
+            for file in &self.runs[index].files {
+                let path = Path::new(file);
+                if !path.exists() {
+                    continue;
+                }
+                std::fs::remove_file(path)
+                    .map_err(|e| format!("Failed to delete {}: {}", file, e))?;
+            }
+        }
+
+        let run = self.runs.remove(index);
+        self.save();
+        Ok(run)
+    }
+
+    fn save(&self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(&self.runs)
+            .map_err(|e| e.to_string())
+            .and_then(|content| write_atomic(file, &content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("Failed to write run manifest {:?}: {}", file, e);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use log::{error, warn};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Write to a temp file next to `path` and rename it into place, so a crash
+// mid-write can't leave a truncated file behind
+pub fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = with_suffix(path, ".tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path)
+}
+
+// Move a file that failed to parse to `<name>.bak`, so saving over it later
+// doesn't lose what it held. Returns false if it couldn't be moved, in which
+// case callers must not write to `path`.
+pub fn set_aside(path: &Path) -> bool {
+    let backup = with_suffix(path, ".bak");
+    match std::fs::rename(path, &backup) {
+        Ok(()) => {
+            warn!("Moved unreadable {:?} to {:?}", path, backup);
+            true
+        }
+        Err(e) => {
+            error!("Failed to move unreadable {:?} aside: {}", path, e);
+            false
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
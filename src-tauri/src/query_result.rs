@@ -0,0 +1,246 @@
+use alloy_primitives::hex;
+use duckdb::types::{TimeUnit, Value};
+use duckdb::{Result, Rows};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::time::Instant;
+
+// Largest integer JavaScript numbers hold exactly; bigger ones are sent as
+// strings so u256-sized values survive the trip to the UI
+const MAX_SAFE_INTEGER: u128 = (1 << 53) - 1;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QueryResult {
+    columns: Vec<QueryColumn>,
+    row_count: usize,
+    elapsed_ms: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct QueryColumn {
+    name: String,
+    // Arrow type of the column as DuckDB reports it
+    data_type: String,
+    values: Vec<JsonValue>,
+}
+
+impl QueryResult {
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    // Read every row into typed columns
+    pub fn from_rows(mut rows: Rows<'_>, started: Instant) -> Result<Self> {
+        let mut columns: Vec<QueryColumn> = match rows.as_ref() {
+            Some(stmt) => stmt
+                .column_names()
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| QueryColumn {
+                    name,
+                    data_type: stmt.column_type(index).to_string(),
+                    values: Vec::new(),
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut row_count = 0;
+        while let Some(row) = rows.next()? {
+            for (index, column) in columns.iter_mut().enumerate() {
+                column.values.push(to_json(row.get::<_, Value>(index)?));
+            }
+            row_count += 1;
+        }
+
+        Ok(QueryResult {
+            columns,
+            row_count,
+            elapsed_ms: started.elapsed().as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+fn to_json(value: Value) -> JsonValue {
+    match value {
+        Value::Null => JsonValue::Null,
+        Value::Boolean(value) => json!(value),
+        Value::TinyInt(value) => json!(value),
+        Value::SmallInt(value) => json!(value),
+        Value::Int(value) => json!(value),
+        Value::BigInt(value) => integer(value as i128),
+        Value::HugeInt(value) => integer(value),
+        Value::UTinyInt(value) => json!(value),
+        Value::USmallInt(value) => json!(value),
+        Value::UInt(value) => json!(value),
+        Value::UBigInt(value) => integer(value as i128),
+        Value::Float(value) => json!(value),
+        Value::Double(value) => json!(value),
+        // Kept exact, the UI can parse it if it needs a number
+        Value::Decimal(value) => json!(value.to_string()),
+        Value::Timestamp(unit, value) => json!(timestamp(unit, value)),
+        Value::Date32(days) => json!(date(days as i64)),
+        Value::Time64(unit, value) => json!(time_of_day(to_micros(unit, value))),
+        Value::Interval {
+            months,
+            days,
+            nanos,
+        } => json!({ "months": months, "days": days, "nanos": nanos }),
+        Value::Text(value) | Value::Enum(value) => json!(value),
+        Value::Blob(bytes) => json!(format!("0x{}", hex::encode(bytes))),
+        Value::List(values) | Value::Array(values) => {
+            JsonValue::Array(values.into_iter().map(to_json).collect())
+        }
+        other => json!(format!("{:?}", other)),
+    }
+}
+
+fn integer(value: i128) -> JsonValue {
+    if value.unsigned_abs() <= MAX_SAFE_INTEGER {
+        json!(value as i64)
+    } else {
+        json!(value.to_string())
+    }
+}
+
+fn to_micros(unit: TimeUnit, value: i64) -> i64 {
+    match unit {
+        TimeUnit::Second => value.saturating_mul(1_000_000),
+        TimeUnit::Millisecond => value.saturating_mul(1_000),
+        TimeUnit::Microsecond => value,
+        TimeUnit::Nanosecond => value / 1_000,
+    }
+}
+
+// ISO-8601 in UTC, e.g. `2024-03-13T13:55:35.000000Z`
+fn timestamp(unit: TimeUnit, value: i64) -> String {
+    // DuckDB stores `infinity` and `-infinity` as the extremes of the range
+    if value == i64::MAX {
+        return "infinity".to_string();
+    }
+    if value == -i64::MAX {
+        return "-infinity".to_string();
+    }
+    let micros = to_micros(unit, value);
+    let days = micros.div_euclid(86_400_000_000);
+    format!(
+        "{}T{}Z",
+        date(days),
+        time_of_day(micros.rem_euclid(86_400_000_000))
+    )
+}
+
+fn time_of_day(micros: i64) -> String {
+    let seconds = micros / 1_000_000;
+    format!(
+        "{:02}:{:02}:{:02}.{:06}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        micros % 1_000_000
+    )
+}
+
+// Days since the Unix epoch to `YYYY-MM-DD`, using the proleptic Gregorian
+// calendar (Howard Hinnant's civil_from_days)
+fn date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(-1), "1969-12-31");
+        assert_eq!(date(-719_162), "0001-01-01");
+        assert_eq!(date(19_723), "2024-01-01");
+    }
+
+    #[test]
+    fn formats_leap_days() {
+        assert_eq!(date(11_016), "2000-02-29");
+        assert_eq!(date(19_782), "2024-02-29");
+        assert_eq!(date(19_783), "2024-03-01");
+        // 1900 isn't a leap year
+        assert_eq!(date(-25_508), "1900-03-01");
+    }
+
+    #[test]
+    fn formats_timestamps_in_every_unit() {
+        assert_eq!(
+            timestamp(TimeUnit::Second, 0),
+            "1970-01-01T00:00:00.000000Z"
+        );
+        assert_eq!(
+            timestamp(TimeUnit::Second, 1_700_000_000),
+            "2023-11-14T22:13:20.000000Z"
+        );
+        assert_eq!(
+            timestamp(TimeUnit::Millisecond, 1_700_000_000_123),
+            "2023-11-14T22:13:20.123000Z"
+        );
+        assert_eq!(
+            timestamp(TimeUnit::Microsecond, 1_700_000_000_123_456),
+            "2023-11-14T22:13:20.123456Z"
+        );
+        assert_eq!(
+            timestamp(TimeUnit::Nanosecond, 1_700_000_000_123_456_789),
+            "2023-11-14T22:13:20.123456Z"
+        );
+    }
+
+    #[test]
+    fn formats_timestamps_before_the_epoch() {
+        assert_eq!(
+            timestamp(TimeUnit::Microsecond, -1),
+            "1969-12-31T23:59:59.999999Z"
+        );
+        assert_eq!(
+            timestamp(TimeUnit::Second, -86_400),
+            "1969-12-31T00:00:00.000000Z"
+        );
+    }
+
+    #[test]
+    fn formats_infinite_timestamps() {
+        assert_eq!(timestamp(TimeUnit::Second, i64::MAX), "infinity");
+        assert_eq!(timestamp(TimeUnit::Millisecond, -i64::MAX), "-infinity");
+        // Large but finite values saturate instead of overflowing
+        assert_eq!(to_micros(TimeUnit::Second, i64::MAX / 2), i64::MAX);
+    }
+
+    #[test]
+    fn formats_times_of_day() {
+        assert_eq!(
+            time_of_day(to_micros(TimeUnit::Second, 45_296)),
+            "12:34:56.000000"
+        );
+        assert_eq!(
+            time_of_day(to_micros(TimeUnit::Nanosecond, 45_296_000_001_000)),
+            "12:34:56.000001"
+        );
+    }
+
+    #[test]
+    fn keeps_large_integers_exact() {
+        assert_eq!(integer(42), json!(42));
+        assert_eq!(integer(-(1 << 53) + 1), json!(-(1_i64 << 53) + 1));
+        assert_eq!(integer(1 << 53), json!("9007199254740992"));
+        assert_eq!(
+            integer(i128::MIN),
+            json!("-170141183460469231731687303715884105728")
+        );
+    }
+}
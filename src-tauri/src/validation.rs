@@ -0,0 +1,475 @@
+use crate::datasets::DatasetSchema;
+use crate::indexer::IndexerOptions;
+use alloy_primitives::{hex, Address, B256};
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+const U256_TYPES: &[&str] = &[
+    "binary",
+    "string",
+    "str",
+    "f32",
+    "f64",
+    "float",
+    "u32",
+    "u64",
+    "decimal128",
+    "d128",
+];
+const COMPRESSIONS: &[&str] = &[
+    "uncompressed",
+    "snappy",
+    "gzip",
+    "lzo",
+    "brotli",
+    "lz4",
+    "zstd",
+];
+const CHUNK_ORDERS: &[&str] = &["normal", "reverse", "random"];
+
+// A problem with a single `IndexerOptions` field, named as the frontend sends
+// it so the message can be attached to the matching input
+#[derive(Debug, Serialize, Clone)]
+pub struct FieldError {
+    field: String,
+    message: String,
+    value: Option<String>,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>, value: Option<&str>) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.into(),
+            value: value.map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}: {} ({})", self.field, self.message, value),
+            None => write!(f, "{}: {}", self.field, self.message),
+        }
+    }
+}
+
+// Check options against the selected datasets before handing them to cryo,
+// which only reports the first problem it runs into
+pub fn validate_options(datasets: &[&DatasetSchema], options: &IndexerOptions) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    if let Some(blocks) = &options.blocks {
+        for spec in blocks.iter().flat_map(|b| b.split_whitespace()) {
+            if let Err(message) = check_block_spec(spec) {
+                errors.push(FieldError::new("blocks", message, Some(spec)));
+            }
+        }
+    }
+    if options.blocks.is_some() && options.timestamps.is_some() {
+        errors.push(FieldError::new(
+            "timestamps",
+            "Blocks and timestamps cannot both be set",
+            None,
+        ));
+    }
+    if let Some(txs) = &options.txs {
+        for tx in txs {
+            if !is_file(tx) && B256::from_str(tx).is_err() {
+                errors.push(FieldError::new(
+                    "txs",
+                    "Not a transaction hash",
+                    Some(tx.as_str()),
+                ));
+            }
+        }
+    }
+
+    let address_fields = [
+        ("address", &options.address),
+        ("contract", &options.contract),
+        ("fromAddress", &options.from_address),
+        ("toAddress", &options.to_address),
+    ];
+    for (field, values) in address_fields {
+        for value in values.iter().flatten() {
+            if !is_file(value) && Address::from_str(value).is_err() {
+                errors.push(FieldError::new(
+                    field,
+                    "Not a valid address",
+                    Some(value.as_str()),
+                ));
+            }
+        }
+    }
+
+    let topic_fields = [
+        ("topic0", &options.topic0),
+        ("topic1", &options.topic1),
+        ("topic2", &options.topic2),
+        ("topic3", &options.topic3),
+    ];
+    for (field, values) in topic_fields {
+        for value in values.iter().flatten() {
+            if B256::from_str(value).is_err() {
+                errors.push(FieldError::new(
+                    field,
+                    "Topics must be 32-byte hex values",
+                    Some(value.as_str()),
+                ));
+            }
+        }
+    }
+    if options.topic0.is_some() && options.event_signature.is_some() {
+        errors.push(FieldError::new(
+            "eventSignature",
+            "An event signature already sets topic0",
+            options.event_signature.as_deref(),
+        ));
+    }
+
+    for value in options.slot.iter().flatten() {
+        if B256::from_str(value).is_err() {
+            errors.push(FieldError::new(
+                "slot",
+                "Storage slots must be 32-byte hex values",
+                Some(value.as_str()),
+            ));
+        }
+    }
+    for value in options.call_data.iter().flatten() {
+        if hex::decode(value).is_err() {
+            errors.push(FieldError::new(
+                "callData",
+                "Not valid hex data",
+                Some(value.as_str()),
+            ));
+        }
+    }
+
+    check_columns(&mut errors, datasets, options);
+    for dataset in datasets {
+        for parameter in dataset.required_parameters() {
+            if !is_parameter_set(options, parameter) {
+                errors.push(FieldError::new(
+                    parameter,
+                    format!("Required by the {} dataset", dataset.name()),
+                    None,
+                ));
+            }
+        }
+    }
+
+    if options.csv && options.json {
+        errors.push(FieldError::new(
+            "json",
+            "Choose either CSV or JSON output",
+            None,
+        ));
+    }
+    if options.chunk_size == 0 {
+        errors.push(FieldError::new(
+            "chunkSize",
+            "Chunk size must be greater than zero",
+            Some("0"),
+        ));
+    }
+    if options.n_chunks == Some(0) {
+        errors.push(FieldError::new(
+            "nChunks",
+            "Number of chunks must be greater than zero",
+            Some("0"),
+        ));
+    }
+    if options.requests_per_second == Some(0) {
+        errors.push(FieldError::new(
+            "requestsPerSecond",
+            "Rate limit must be greater than zero",
+            Some("0"),
+        ));
+    }
+    for value in options.u256_types.iter().flatten() {
+        if !U256_TYPES.contains(&value.as_str()) {
+            errors.push(FieldError::new(
+                "u256Types",
+                format!("Expected one of: {}", U256_TYPES.join(", ")),
+                Some(value.as_str()),
+            ));
+        }
+    }
+    if let Some(algorithm) = options.compression.first() {
+        if !COMPRESSIONS.contains(&algorithm.as_str()) {
+            errors.push(FieldError::new(
+                "compression",
+                format!("Expected one of: {}", COMPRESSIONS.join(", ")),
+                Some(algorithm.as_str()),
+            ));
+        }
+    }
+    if let Some(order) = &options.chunk_order {
+        if !CHUNK_ORDERS.contains(&order.as_str()) {
+            errors.push(FieldError::new(
+                "chunkOrder",
+                format!("Expected one of: {}", CHUNK_ORDERS.join(", ")),
+                Some(order.as_str()),
+            ));
+        }
+    }
+
+    errors
+}
+
+fn check_columns(
+    errors: &mut Vec<FieldError>,
+    datasets: &[&DatasetSchema],
+    options: &IndexerOptions,
+) {
+    let column_fields = [
+        ("columns", &options.columns),
+        ("includeColumns", &options.include_columns),
+        ("excludeColumns", &options.exclude_columns),
+        ("sort", &options.sort),
+    ];
+    for (field, columns) in column_fields {
+        for column in columns.iter().flatten() {
+            // Cryo shorthands for every or no column
+            if column == "all" || column == "none" {
+                continue;
+            }
+            let missing: Vec<&str> = datasets
+                .iter()
+                .filter(|dataset| !dataset.has_column(column))
+                .map(|dataset| dataset.name())
+                .collect();
+            if !missing.is_empty() {
+                errors.push(FieldError::new(
+                    field,
+                    format!("Unknown column for {}", missing.join(", ")),
+                    Some(column.as_str()),
+                ));
+            }
+        }
+    }
+}
+
+fn is_parameter_set(options: &IndexerOptions, parameter: &str) -> bool {
+    match parameter {
+        "blocks" => options.blocks.is_some() || options.timestamps.is_some(),
+        "txs" => options.txs.is_some(),
+        "callData" => options.call_data.is_some() || options.function.is_some(),
+        "address" => options.address.is_some(),
+        "contract" => options.contract.is_some(),
+        "fromAddress" => options.from_address.is_some(),
+        "toAddress" => options.to_address.is_some(),
+        "slot" => options.slot.is_some(),
+        "topic0" => options.topic0.is_some() || options.event_signature.is_some(),
+        "topic1" => options.topic1.is_some(),
+        "topic2" => options.topic2.is_some(),
+        "topic3" => options.topic3.is_some(),
+        // Dimensions the form doesn't expose are left to cryo
+        _ => true,
+    }
+}
+
+// Parameter values can also point at parquet/csv files of inputs
+fn is_file(value: &str) -> bool {
+    Path::new(value).is_file()
+}
+
+// Cryo block syntax: numbers like `5000`, `15.5M` or `5_000`, `latest`,
+// ranges `start:end` with optional `-` on the start or `+` on the end, a
+// `:step` or `/count` suffix, and parquet files
+fn check_block_spec(spec: &str) -> Result<(), String> {
+    if spec.contains(".parquet") {
+        return Ok(());
+    }
+    let spec = match spec.split_once('/') {
+        Some((range, count)) => {
+            parse_block_number(count).ok_or("Invalid block count")?;
+            range
+        }
+        None => spec,
+    };
+
+    let parts: Vec<&str> = spec.split(':').collect();
+    match parts.as_slice() {
+        [block] => {
+            if *block != "latest" && parse_block_number(block).is_none() {
+                return Err("Invalid block number".to_string());
+            }
+        }
+        [start, end] | [start, end, _] => {
+            let start_number = match start.strip_prefix('-') {
+                Some(offset) => parse_block_number(offset).map(|_| None),
+                None if start.is_empty() => Some(Some(0)),
+                None => parse_block_number(start).map(Some),
+            }
+            .ok_or("Invalid range start")?;
+            let end_number = match end.strip_prefix('+') {
+                Some(offset) => parse_block_number(offset).map(|_| None),
+                None if end.is_empty() || *end == "latest" => Some(None),
+                None => parse_block_number(end).map(Some),
+            }
+            .ok_or("Invalid range end")?;
+            if let (Some(start), Some(end)) = (start_number, end_number) {
+                if start > end {
+                    return Err("Range start is after its end".to_string());
+                }
+            }
+            if let [_, _, step] = parts.as_slice() {
+                match parse_block_number(step) {
+                    Some(step) if step > 0 => {}
+                    _ => return Err("Invalid range step".to_string()),
+                }
+            }
+        }
+        _ => return Err("Invalid block range".to_string()),
+    }
+    Ok(())
+}
+
+fn parse_block_number(value: &str) -> Option<u64> {
+    let value = value.replace('_', "");
+    let (number, multiplier) = match value.chars().last()? {
+        'K' | 'k' => (&value[..value.len() - 1], 1e3),
+        'M' | 'm' => (&value[..value.len() - 1], 1e6),
+        'B' | 'b' => (&value[..value.len() - 1], 1e9),
+        _ => (value.as_str(), 1.0),
+    };
+    if multiplier == 1.0 {
+        return number.parse().ok();
+    }
+    let number: f64 = number.parse().ok()?;
+    (number >= 0.0).then(|| (number * multiplier).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(overrides: serde_json::Value) -> IndexerOptions {
+        let mut options = json!({
+            "align": false,
+            "reorgBuffer": 0,
+            "hex": false,
+            "excludeFailed": false,
+            "maxRetries": 5,
+            "initialBackoff": 500,
+            "dry": false,
+            "chunkSize": 1000,
+            "subdirs": [],
+            "overwrite": false,
+            "csv": false,
+            "json": false,
+            "noStats": false,
+            "compression": ["lz4"],
+            "noReport": false,
+            "innerRequestSize": 1,
+        });
+        for (key, value) in overrides.as_object().unwrap() {
+            options[key] = value.clone();
+        }
+        serde_json::from_value(options).unwrap()
+    }
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_block_numbers() {
+        assert_eq!(parse_block_number("5000"), Some(5000));
+        assert_eq!(parse_block_number("5_000"), Some(5000));
+        assert_eq!(parse_block_number("2k"), Some(2000));
+        assert_eq!(parse_block_number("15.5M"), Some(15_500_000));
+        assert_eq!(parse_block_number("1B"), Some(1_000_000_000));
+        assert_eq!(parse_block_number("1.5"), None);
+        assert_eq!(parse_block_number("-1M"), None);
+        assert_eq!(parse_block_number("abc"), None);
+        assert_eq!(parse_block_number(""), None);
+    }
+
+    #[test]
+    fn accepts_cryo_block_specs() {
+        for spec in [
+            "5000",
+            "15.5M",
+            "latest",
+            "100:200",
+            ":200",
+            "100:",
+            "-1000:",
+            "100:+50",
+            "1k:latest",
+            "1M:2M:1000",
+            "0:1000/10",
+            "blocks.parquet",
+        ] {
+            assert_eq!(check_block_spec(spec), Ok(()), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_block_specs() {
+        for spec in [
+            "abc", "1.5", "200:100", "x:100", "100:y", "-x:", "100:+", "1:2:0", "1:2:x", "1:2:3:4",
+            "0:100/z",
+        ] {
+            assert!(check_block_spec(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn reports_each_invalid_block_spec() {
+        let errors = validate_options(&[], &options(json!({ "blocks": ["100:200 oops", "1:0"] })));
+        let values: Vec<_> = errors.iter().filter_map(|e| e.value.as_deref()).collect();
+        assert_eq!(fields(&errors), ["blocks", "blocks"]);
+        assert_eq!(values, ["oops", "1:0"]);
+    }
+
+    #[test]
+    fn checks_columns_against_every_dataset() {
+        let blocks = DatasetSchema::for_test("blocks", &["block_number", "block_hash"], &[]);
+        let txs =
+            DatasetSchema::for_test("transactions", &["block_number", "transaction_hash"], &[]);
+        let datasets = [&blocks, &txs];
+
+        let shared = options(json!({ "columns": ["block_number"], "sort": ["block_number"] }));
+        assert!(validate_options(&datasets, &shared).is_empty());
+
+        let shorthand = options(json!({ "includeColumns": ["all"], "excludeColumns": ["none"] }));
+        assert!(validate_options(&datasets, &shorthand).is_empty());
+
+        let partial = options(json!({ "columns": ["block_hash", "gas_used"] }));
+        let errors = validate_options(&datasets, &partial);
+        let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(fields(&errors), ["columns", "columns"]);
+        assert_eq!(
+            messages,
+            [
+                "Unknown column for transactions",
+                "Unknown column for blocks, transactions"
+            ]
+        );
+    }
+
+    #[test]
+    fn requires_dataset_parameters() {
+        let logs = DatasetSchema::for_test("logs", &["address"], &["blocks", "topic0"]);
+        let slots = DatasetSchema::for_test("slots", &["slot"], &["address"]);
+
+        let errors = validate_options(&[&logs, &slots], &options(json!({})));
+        assert_eq!(fields(&errors), ["blocks", "topic0", "address"]);
+
+        let set = options(json!({
+            "timestamps": ["1700000000:1700003600"],
+            "eventSignature": "Transfer(address,address,uint256)",
+            "address": ["0x00000000219ab540356cbb839cbe05303d7705fa"],
+        }));
+        assert!(validate_options(&[&logs, &slots], &set).is_empty());
+    }
+}
@@ -19,6 +19,7 @@ import {
   Loader2,
   Play,
   Search,
+  Square,
 } from 'lucide-react'
 import { useCallback, useEffect, useState } from 'react'
 import { toast } from 'sonner'
@@ -26,10 +27,41 @@ import type { FileInfo } from '../types/chainAnalyzer'
 import DataVisualizationChart from './DataVisualizationChart'
 import { VirtualizedDataTable } from './VirtualizedDataTable'
 
+interface QueryColumn {
+  name: string
+  data_type: string
+  values: unknown[]
+}
+
 interface QueryResult {
-  json: string
+  columns: QueryColumn[]
+  row_count: number
+  elapsed_ms: number
+}
+
+interface QueryCursor {
+  cursor_id: number
+  columns: { name: string; data_type: string }[]
+  row_count: number
+  truncated: boolean
+  total_rows: number | null
+  elapsed_ms: number
 }
 
+// Rows loaded into the table per query; the cursor stops there too
+const PAGE_SIZE = 10000
+
+// Queries that can be wrapped in a subquery go through a cursor; anything
+// else (CREATE, SET, PRAGMA, SHOW...) runs as a plain statement
+const ROW_QUERY = /^(select|with|from|values|table)\b/i
+const LEADING_NOISE = /^(\s+|--[^\n]*(\n|$)|\/\*[\s\S]*?\*\/|\()*/
+
+const isRowQuery = (query: string) =>
+  ROW_QUERY.test(query.replace(LEADING_NOISE, ''))
+
+// Queries still running after this are interrupted by the backend
+const QUERY_TIMEOUT_MS = 5 * 60 * 1000
+
 interface DataRow {
   [key: string]: string | number | boolean | null
 }
@@ -55,6 +87,7 @@ const ChainAnalyzer: React.FC = () => {
   } = useChainAnalyzerStore()
   const [searchTerm, setSearchTerm] = useState('')
   const [isLoading, setIsLoading] = useState(false)
+  const [runningQueryId, setRunningQueryId] = useState<string | null>(null)
   const [xAxis, setXAxis] = useState<string>('')
   const [yAxis, setYAxis] = useState<string>('')
   const [showBrush, setShowBrush] = useState(false)
@@ -100,6 +133,8 @@ const ChainAnalyzer: React.FC = () => {
     setQueryError(null)
     setIsLoading(true)
     const startTime = performance.now()
+    const queryId = crypto.randomUUID()
+    setRunningQueryId(queryId)
 
     try {
       const modifiedQuery = sqlQuery.replace(
@@ -111,19 +146,42 @@ const ChainAnalyzer: React.FC = () => {
       )
 
       console.log(`Executing query: ${modifiedQuery}`)
-      const result: QueryResult = await invoke('execute_query_command', {
-        query: modifiedQuery,
-      })
-
-      const parsedData = JSON.parse(result.json)
-      console.log('Parsed data:', parsedData)
+      let result: QueryResult
+      if (isRowQuery(modifiedQuery)) {
+        const cursor: QueryCursor = await invoke('open_query_cursor', {
+          queryId,
+          query: modifiedQuery,
+          rowCap: PAGE_SIZE,
+          timeoutMs: QUERY_TIMEOUT_MS,
+        })
+        result = await invoke<QueryResult>('fetch_query_page', {
+          cursorId: cursor.cursor_id,
+          offset: 0,
+          limit: PAGE_SIZE,
+        }).finally(() =>
+          invoke('close_query_cursor', { cursorId: cursor.cursor_id }),
+        )
+        if (cursor.truncated) {
+          const total =
+            cursor.total_rows !== null
+              ? `~${cursor.total_rows}`
+              : `more than ${cursor.row_count}`
+          toast.info(`Showing the first ${cursor.row_count} of ${total} rows`)
+        }
+      } else {
+        result = await invoke('execute_query_command', {
+          queryId,
+          query: modifiedQuery,
+          timeoutMs: QUERY_TIMEOUT_MS,
+        })
+      }
 
-      if (parsedData[0] && parsedData[0].length > 0) {
-        const columns = parsedData[0].map((col: [string, unknown[]]) => col[0])
-        const data = parsedData[0][0][1].map((_: unknown, index: number) => {
-          const row: { [key: string]: unknown } = {}
-          for (const col of parsedData[0]) {
-            row[col[0]] = col[1][index]
+      if (result.columns.length > 0) {
+        const columns = result.columns.map((col) => col.name)
+        const data = Array.from({ length: result.row_count }, (_, index) => {
+          const row: DataRow = {}
+          for (const col of result.columns) {
+            row[col.name] = col.values[index] as DataRow[string]
           }
           return row
         })
@@ -147,7 +205,7 @@ const ChainAnalyzer: React.FC = () => {
 
         // Update queryResult
         setQueryResult({
-          schema: columns.map((name: string) => [name, '']),
+          schema: result.columns.map((col) => [col.name, col.data_type]),
           data: data.map((row: DataRow) =>
             columns.map((col: string) => row[col]),
           ),
@@ -167,9 +225,19 @@ const ChainAnalyzer: React.FC = () => {
       toast.error(`Query execution failed: ${error}`)
     } finally {
       setIsLoading(false)
+      setRunningQueryId(null)
     }
   }, [sqlQuery, files, setQueryError, setQueryTime, setQueryResult])
 
+  const interruptQuery = async () => {
+    if (!runningQueryId) return
+    try {
+      await invoke('interrupt_query', { queryId: runningQueryId })
+    } catch (error) {
+      console.error('Interrupt error:', error)
+    }
+  }
+
   const previewFile = async (filePath: string) => {
     try {
       console.log(`Previewing file: ${filePath}`)
@@ -330,6 +398,16 @@ const ChainAnalyzer: React.FC = () => {
                 </>
               )}
             </Button>
+            {runningQueryId && (
+              <Button
+                onClick={interruptQuery}
+                className="text-xs"
+                variant="outline"
+              >
+                <Square className="mr-2 h-3 w-3" />
+                Stop
+              </Button>
+            )}
             <div className="flex items-center text-xs">
               {queryResult && (
                 <Button onClick={downloadResults} size="icon" variant="ghost">